@@ -17,7 +17,7 @@ pub fn print_ast(statements: &Vec<Stmt>) {
 }
 
 fn print_stmt(statement: &Stmt) -> String {
-    return match statement {
+    match statement {
         Stmt::AssignmentStmt { expression } => {
             return print_expr(expression).as_str().into();
         }
@@ -27,7 +27,7 @@ fn print_stmt(statement: &Stmt) -> String {
         Stmt::PrintStmt { expression } => {
             return format!("print \"{}\"", print_expr(expression));
         }
-        Stmt::VarDeclStmt { name, initializer } => {
+        Stmt::VarDeclStmt { name, initializer, .. } => {
             return format!("var {} = {}", name.lexeme, print_expr(initializer));
         }
         Stmt::BlockStmt { statements } => {
@@ -56,25 +56,87 @@ fn print_stmt(statement: &Stmt) -> String {
             ret.push_str(format!("\n else \n\t{:?}\n", else_branch).as_str());
             return ret;
         }
-        Stmt::WhileStmt { .. } => {
-            todo!()
+        Stmt::WhileStmt {
+            condition,
+            body,
+            increment,
+            label,
+            ..
+        } => {
+            let mut ret = String::new();
+            if let Some(label) = label {
+                ret += format!("{}: ", label.lexeme).as_str();
+            }
+
+            ret += format!("while {} {}", print_expr(condition), print_stmt(body)).as_str();
+            if let Some(increment) = increment {
+                ret += format!(" then {}", print_stmt(increment)).as_str();
+            }
+
+            return ret;
+        }
+        Stmt::FunctionStmt { name, params, body, .. } => {
+            let params = params
+                .iter()
+                .map(|param| param.lexeme.as_str())
+                .collect::<Vec<&str>>()
+                .join(", ");
+
+            let mut ret = format!("fun {}({}) {{\n", name.lexeme, params);
+            for statement in body {
+                ret += "\t";
+                ret += print_stmt(statement).as_str();
+                ret += "\n";
+            }
+            ret += "}";
+
+            return ret;
         }
-        Stmt::FunctionStmt { .. } => {
-            todo!()
+        Stmt::ReturnStmt { value, .. } => {
+            return match value {
+                Some(value) => format!("return {}", print_expr(value)),
+                None => "return".into(),
+            };
         }
-        Stmt::ReturnStmt { .. } => {
-            todo!()
+        Stmt::BreakStmt { label, .. } => {
+            return match label {
+                Some(label) => format!("break {}", label.lexeme),
+                None => "break".into(),
+            };
         }
-        Stmt::ClassStmt { .. } => {
-            todo!()
+        Stmt::ContinueStmt { label, .. } => {
+            return match label {
+                Some(label) => format!("continue {}", label.lexeme),
+                None => "continue".into(),
+            };
         }
-        Stmt::None { err } => err.into(),
-    };
+        Stmt::ClassStmt {
+            name,
+            methods,
+            superclass,
+            ..
+        } => {
+            let mut ret = format!("class {}", name.lexeme);
+            if let Some(superclass) = superclass {
+                ret += format!(" < {}", print_expr(superclass)).as_str();
+            }
+
+            ret += " {\n";
+            for method in methods {
+                ret += "\t";
+                ret += print_stmt(method).as_str();
+                ret += "\n";
+            }
+            ret += "}";
+
+            return ret;
+        }
+    }
 }
 
 fn print_expr(expr: &Expr) -> String {
     return match expr {
-        Expr::AssignmentExpression { name, value } => {
+        Expr::AssignmentExpression { name, value, .. } => {
             format!("= {} {}", name.lexeme, print_expr(value))
         }
         Expr::BinaryExpression {
@@ -89,6 +151,14 @@ fn print_expr(expr: &Expr) -> String {
                 print_expr(right)
             )
         }
+        Expr::CompoundAssignmentExpression {
+            target,
+            operator,
+            value,
+            ..
+        } => {
+            format!("{} {} {}", operator.lexeme, print_expr(target), print_expr(value))
+        }
         Expr::CallExpression {
             arguments, callee, ..
         } => {
@@ -98,13 +168,30 @@ fn print_expr(expr: &Expr) -> String {
                 print_expr_vec(arguments)
             )
         }
+        Expr::ConditionalExpression {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            format!(
+                "{} ? {} : {}",
+                print_expr(condition),
+                print_expr(then_branch),
+                print_expr(else_branch)
+            )
+        }
         Expr::GetExpression { object, name } => {
             format!(".{} {}", print_expr(object), name.lexeme)
         }
-        Expr::GroupingExpression { expression } => {
+        Expr::GroupingExpression { expression, .. } => {
             format!("(group {})", print_expr(expression))
         }
-        Expr::LiteralExpression { value } => {
+        Expr::InterpolationExpression { parts } => {
+            let parts: Vec<String> = parts.iter().map(|part| print_expr(part)).collect();
+
+            format!("(interpolate {})", parts.join(" "))
+        }
+        Expr::LiteralExpression { value, .. } => {
             format!("{}", value.as_ref().unwrap_or(&Literal::Nil))
         }
         Expr::LogicalExpression {
@@ -135,17 +222,12 @@ fn print_expr(expr: &Expr) -> String {
             format!("super.{}", method.lexeme)
         }
         Expr::SelfExpression { .. } => {
-            format!("self")
+            "self".into()
         }
         Expr::UnaryExpression { operator, right } => {
             format!("({} {})", operator.lexeme, print_expr(right))
         }
-        Expr::VarDeclExpression { name } => {
-            format!("{}", name.lexeme)
-        }
-        Expr::VariableResolutionExpression { name } => {
-            format!("{}", name.lexeme)
-        }
+        Expr::VariableResolutionExpression { name, .. } => name.lexeme.clone(),
     };
 }
 
@@ -161,6 +243,7 @@ fn print_expr_vec(expressions: &Vec<Box<Expr>>) -> String {
 
 #[cfg(test)]
 mod tests {
+    use crate::span::Span;
     use crate::token::Token;
     use crate::token_kinds::TokenKind;
 
@@ -179,10 +262,15 @@ mod tests {
                                 line: 1,
                                 column: 1,
                                 literal: None,
+                                span: Span::default(),
+                                doc: None,
+                                leading_trivia: Vec::new(),
+                                trailing_trivia: Vec::new(),
                             },
                             right: Box::new(
                                 Expr::LiteralExpression {
                                     value: Some(Literal::Number(123.into())),
+                                    span: Span::default(),
                                 }
                             ),
                         }
@@ -193,12 +281,18 @@ mod tests {
                         line: 1,
                         column: 2,
                         literal: None,
+                        span: Span::default(),
+                        doc: None,
+                        leading_trivia: Vec::new(),
+                        trailing_trivia: Vec::new(),
                     },
                     right: Box::new(
                         Expr::GroupingExpression {
+                            span: Span::default(),
                             expression: Box::new(
                                 Expr::LiteralExpression {
-                                    value: Some(Literal::Number(45.67.into())),
+                                    value: Some(Literal::Number(45.67)),
+                                    span: Span::default(),
                                 }
                             ),
                         }
@@ -216,6 +310,7 @@ mod tests {
             expression: Box::new(
                 Expr::LiteralExpression {
                     value: Some(Literal::String("Hello, world!".into())),
+                    span: Span::default(),
                 }
             ),
         };
@@ -232,10 +327,16 @@ mod tests {
                 line: 1,
                 column: 1,
                 literal: None,
+                span: Span::default(),
+                doc: None,
+                leading_trivia: Vec::new(),
+                trailing_trivia: Vec::new(),
             },
-            initializer: Expr::LiteralExpression {
+            initializer: Box::new(Expr::LiteralExpression {
                 value: Some(Literal::Number(1.into())),
-            },
+                span: Span::default(),
+            }),
+            doc: None,
         };
 
         assert_eq!(print_stmt(&stmt), "var a = 1");
@@ -252,12 +353,18 @@ mod tests {
                         line: 1,
                         column: 1,
                         literal: None,
+                        span: Span::default(),
+                        doc: None,
+                        leading_trivia: Vec::new(),
+                        trailing_trivia: Vec::new(),
                     },
                     value: Box::new(
                         Expr::LiteralExpression {
                             value: Some(Literal::Number(1.into())),
+                            span: Span::default(),
                         }
                     ),
+                    binding: None,
                 }
             ),
         };
@@ -276,10 +383,16 @@ mod tests {
                         line: 1,
                         column: 1,
                         literal: None,
+                        span: Span::default(),
+                        doc: None,
+                        leading_trivia: Vec::new(),
+                        trailing_trivia: Vec::new(),
                     },
-                    initializer: Expr::LiteralExpression {
+                    initializer: Box::new(Expr::LiteralExpression {
                         value: Some(Literal::Number(1.into())),
-                    },
+                        span: Span::default(),
+                    }),
+                    doc: None,
                 },
                 Stmt::VarDeclStmt {
                     name: Token {
@@ -288,10 +401,16 @@ mod tests {
                         line: 1,
                         column: 1,
                         literal: None,
+                        span: Span::default(),
+                        doc: None,
+                        leading_trivia: Vec::new(),
+                        trailing_trivia: Vec::new(),
                     },
-                    initializer: Expr::LiteralExpression {
+                    initializer: Box::new(Expr::LiteralExpression {
                         value: Some(Literal::Number(2.into())),
-                    },
+                        span: Span::default(),
+                    }),
+                    doc: None,
                 },
             ],
         };
@@ -0,0 +1,99 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+use crate::function::Function;
+use crate::value::Value;
+
+/// Class is a user-defined class. Calling it creates a new instance of it.
+pub struct Class {
+    pub name: String,
+    pub superclass: Option<Rc<Class>>,
+    pub methods: HashMap<String, Rc<Function>>,
+}
+
+impl Class {
+    /// Looks up a method on this class, then on its superclasses.
+    pub fn find_method(&self, name: &str) -> Option<Rc<Function>> {
+        if let Some(method) = self.methods.get(name) {
+            return Some(Rc::clone(method));
+        }
+
+        return match &self.superclass {
+            Some(superclass) => superclass.find_method(name),
+            None => None,
+        };
+    }
+
+    /// The number of arguments calling the class expects. That's the arity of its initializer, if
+    /// it has one.
+    pub fn arity(&self) -> usize {
+        return match self.find_method("init") {
+            Some(initializer) => initializer.arity(),
+            None => 0,
+        };
+    }
+}
+
+impl PartialEq for Class {
+    /// Classes are only equal to themselves.
+    fn eq(&self, other: &Self) -> bool {
+        return std::ptr::eq(self, other);
+    }
+}
+
+impl Debug for Class {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{}", self.name);
+    }
+}
+
+/// Instance is an object created by calling a class. It holds its own fields and shares its
+/// methods with the other instances of the class.
+pub struct Instance {
+    pub class: Rc<Class>,
+    fields: HashMap<String, Value>,
+}
+
+impl Instance {
+    pub fn new(class: Rc<Class>) -> Instance {
+        return Instance {
+            class,
+            fields: HashMap::new(),
+        };
+    }
+
+    /// Looks up a property on the given instance. Fields shadow methods. Methods are returned
+    /// bound to the instance.
+    pub fn get(instance: &Rc<RefCell<Instance>>, name: &str) -> Option<Value> {
+        if let Some(value) = instance.borrow().fields.get(name) {
+            return Some(value.clone());
+        }
+
+        let method = instance.borrow().class.find_method(name)?;
+
+        return Some(Value::Function(Rc::new(
+            method.bind(Value::Instance(Rc::clone(instance))),
+        )));
+    }
+
+    /// Sets a field on this instance, creating it if it doesn't exist.
+    pub fn set(&mut self, name: String, value: Value) {
+        self.fields.insert(name, value);
+    }
+}
+
+impl PartialEq for Instance {
+    /// Instances are only equal to themselves.
+    fn eq(&self, other: &Self) -> bool {
+        return std::ptr::eq(self, other);
+    }
+}
+
+impl Debug for Instance {
+    // Fields are left out on purpose. They can hold the instance itself.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{} instance", self.class.name);
+    }
+}
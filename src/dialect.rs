@@ -0,0 +1,32 @@
+/// Dialect selects between this implementation's own semantics and the ones of the reference Lox
+/// implementation from Crafting Interpreters.
+///
+/// Both dialects compare values the same way: values of different types are never equal, and `!=`
+/// is always the opposite of `==`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Dialect {
+    /// This implementation's semantics:
+    /// * `0`, `""` and empty lists are falsey, along with `nil` and `false`.
+    /// * `+` concatenates a number and a string.
+    /// * Dividing by zero is an error.
+    /// * A variable can't reuse the name of a variable it's nested in.
+    #[default]
+    Extended,
+    /// The reference semantics:
+    /// * Only `nil` and `false` are falsey.
+    /// * `+` only adds two numbers or concatenates two strings.
+    /// * Dividing by zero follows IEEE 754, and `NaN`, `-0` and infinities print like the book.
+    /// * Variables can shadow the variables of enclosing scopes, and globals can be redeclared.
+    Reference,
+}
+
+impl Dialect {
+    /// Parses the name the dialect is given on the command line.
+    pub fn from_name(name: &str) -> Option<Dialect> {
+        return match name {
+            "extended" => Some(Dialect::Extended),
+            "reference" => Some(Dialect::Reference),
+            _ => None,
+        };
+    }
+}
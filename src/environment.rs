@@ -0,0 +1,121 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::value::Value;
+
+/// A shared handle to an environment. Closures keep the environment they were declared in alive
+/// through it.
+pub type Env = Rc<RefCell<Environment>>;
+
+/// Environment stores the symbols declared in a single scope, and a link to the scope enclosing it.
+///
+/// The global scope stores its symbols by name, since the REPL can declare new ones at any time.
+/// Local scopes store them in slots, in the order they were declared. The resolver computes which
+/// slot every local variable lives in before the code runs.
+#[derive(Debug, Default)]
+pub struct Environment {
+    values: HashMap<String, Value>,
+    slots: Vec<Value>,
+    enclosing: Option<Env>,
+}
+
+impl Environment {
+    /// Creates the outermost environment. It has no enclosing scope.
+    pub fn new_global() -> Env {
+        return Rc::new(RefCell::new(Environment::default()));
+    }
+
+    /// Creates a new environment nested inside `enclosing`.
+    pub fn new_enclosed(enclosing: &Env) -> Env {
+        return Rc::new(RefCell::new(Environment {
+            values: HashMap::new(),
+            slots: Vec::new(),
+            enclosing: Some(Rc::clone(enclosing)),
+        }));
+    }
+
+    /// Creates a new environment holding copies of the symbols of `env`, nested inside the same
+    /// scope. Changes to the copy don't affect `env`, and the other way around.
+    pub fn new_copy(env: &Env) -> Env {
+        let env = env.borrow();
+
+        return Rc::new(RefCell::new(Environment {
+            values: env.values.clone(),
+            slots: env.slots.clone(),
+            enclosing: env.enclosing.clone(),
+        }));
+    }
+
+    /// Adds the given symbol to this scope. Globals overwrite any previous value they had. Locals
+    /// take the next free slot, which is the one the resolver assigned to them.
+    pub fn define(&mut self, name: String, value: Value) {
+        match self.enclosing {
+            Some(_) => self.slots.push(value),
+            None => {
+                self.values.insert(name, value);
+            }
+        }
+    }
+
+    /// Looks up the given global symbol.
+    pub fn get(&self, name: &str) -> Option<Value> {
+        return match &self.enclosing {
+            Some(enclosing) => enclosing.borrow().get(name),
+            None => self.values.get(name).cloned(),
+        };
+    }
+
+    /// Updates the given global symbol. Returns false if it was never declared.
+    pub fn assign(&mut self, name: &str, value: Value) -> bool {
+        return match &self.enclosing {
+            Some(enclosing) => enclosing.borrow_mut().assign(name, value),
+            None => match self.values.get_mut(name) {
+                Some(slot) => {
+                    *slot = value;
+                    true
+                }
+                None => false,
+            },
+        };
+    }
+
+    /// Looks up the local symbol at `slot` of the scope `depth` scopes out from `env`.
+    pub fn get_at(env: &Env, depth: usize, slot: usize) -> Option<Value> {
+        let scope = Environment::ancestor(env, depth);
+        let value = scope.borrow().slots.get(slot).cloned();
+
+        return value;
+    }
+
+    /// Updates the local symbol at `slot` of the scope `depth` scopes out from `env`. Returns false
+    /// if that slot wasn't declared yet.
+    pub fn assign_at(env: &Env, depth: usize, slot: usize, value: Value) -> bool {
+        let scope = Environment::ancestor(env, depth);
+        let mut scope = scope.borrow_mut();
+
+        return match scope.slots.get_mut(slot) {
+            Some(local) => {
+                *local = value;
+                true
+            }
+            None => false,
+        };
+    }
+
+    /// Returns the scope `depth` scopes out from `env`.
+    fn ancestor(env: &Env, depth: usize) -> Env {
+        let mut env = Rc::clone(env);
+        for _ in 0..depth {
+            let enclosing = match &env.borrow().enclosing {
+                Some(enclosing) => Rc::clone(enclosing),
+                // The resolver never binds past the outermost local scope.
+                None => break,
+            };
+
+            env = enclosing;
+        }
+
+        return env;
+    }
+}
@@ -1,6 +1,16 @@
 use crate::literal::Literal;
+use crate::span::Span;
 use crate::token::Token;
 
+/// Binding is where the resolver found a local variable: in the scope `depth` scopes out from the
+/// one it's used in, at index `slot` of that scope. Global variables have no binding and are
+/// looked up by name.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Binding {
+    pub depth: usize,
+    pub slot: usize,
+}
+
 /// A trait that represents an expression in the AST.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Expr {
@@ -23,6 +33,17 @@ pub enum Expr {
         paren: Token,
         arguments: Vec<Box<Expr>>,
     },
+    /// Conditional expressions are expressions that evaluate to one of two branches, depending on
+    /// their condition. Only the branch that's picked is evaluated.
+    /// ## Example
+    /// ```
+    /// count == 1 ? "item" : "items"
+    /// ```
+    ConditionalExpression {
+        condition: Box<Expr>,
+        then_branch: Box<Expr>,
+        else_branch: Box<Expr>,
+    },
     /// Get expressions are expressions that get a property from an object
     /// ## Example
     /// ```
@@ -34,13 +55,24 @@ pub enum Expr {
     /// ```
     /// (1 + 2)
     /// ```
-    GroupingExpression { expression: Box<Expr> },
+    GroupingExpression {
+        expression: Box<Expr>,
+        /// Covers the parentheses, which aren't kept in the AST.
+        span: Span,
+    },
+    /// Interpolation expressions are strings with expressions embedded in them. The text around
+    /// the expressions is kept as string literals, in order.
+    /// ## Example
+    /// ```
+    /// "Hello, ${name}!"
+    /// ```
+    InterpolationExpression { parts: Vec<Box<Expr>> },
     /// Literal expressions are expressions that are literals.
     /// ## Example
     /// ```
     /// 1
     /// ```
-    LiteralExpression { value: Option<Literal> },
+    LiteralExpression { value: Option<Literal>, span: Span },
     /// Logical expressions are expressions that are logical.
     /// ## Example
     /// ```
@@ -66,35 +98,90 @@ pub enum Expr {
     /// ```
     /// super.method();
     /// ```
-    SuperExpression { keyword: Token, method: Token },
+    SuperExpression {
+        keyword: Token,
+        method: Token,
+        binding: Option<Binding>,
+    },
     /// Self expressions are expressions that call a method on the current class.
     /// ## Example
     /// ```
     /// self.method();
     /// ```
-    SelfExpression { keyword: Token },
+    SelfExpression {
+        keyword: Token,
+        binding: Option<Binding>,
+    },
     /// Unary expressions are expressions that have a single side and an operator.
     /// ## Example
     /// ```
     /// !true
     /// ```
     UnaryExpression { operator: Token, right: Box<Expr> },
-    /// Variable expressions are expressions that are variables.
-    /// ## Example
-    /// ```
-    /// var x = 1;
-    /// ```
-    VarDeclExpression { name: Token },
     /// Variable resolution expressions are expressions that resolve a variable.
     /// ## Example
     /// ```
     /// x
     /// ```
-    VariableResolutionExpression { name: Token },
+    VariableResolutionExpression {
+        name: Token,
+        binding: Option<Binding>,
+    },
     /// Assign expressions are expressions that assign a value to a variable.
     /// ## Example
     /// ```
     /// x = 1;
     /// ```
-    AssignmentExpression { name: Token, value: Box<Expr> },
+    AssignmentExpression {
+        name: Token,
+        value: Box<Expr>,
+        binding: Option<Binding>,
+    },
+    /// Compound assignment expressions are expressions that apply a binary operator to a
+    /// variable or property and store the result back in it. The parts of the target are only
+    /// evaluated once. `++` and `--` are compound assignments of 1.
+    /// ## Example
+    /// ```
+    /// object.count += 1;
+    /// count++;
+    /// ```
+    CompoundAssignmentExpression {
+        /// A `VariableResolutionExpression` or `GetExpression`.
+        target: Box<Expr>,
+        operator: Token,
+        value: Box<Expr>,
+        /// Set for `target++` and `target--`, which evaluate to the value before the update. The
+        /// others evaluate to the new value.
+        postfix: bool,
+    },
+}
+
+impl Expr {
+    /// The part of the source code the expression was parsed from.
+    pub fn span(&self) -> Span {
+        return match self {
+            Expr::AssignmentExpression { name, value, .. } => name.span.to(value.span()),
+            Expr::CompoundAssignmentExpression { target, value, .. } => target.span().to(value.span()),
+            Expr::BinaryExpression { left, right, .. }
+            | Expr::LogicalExpression { left, right, .. } => left.span().to(right.span()),
+            Expr::CallExpression { callee, paren, .. } => callee.span().to(paren.span),
+            Expr::ConditionalExpression {
+                condition,
+                else_branch,
+                ..
+            } => condition.span().to(else_branch.span()),
+            Expr::GetExpression { object, name } => object.span().to(name.span),
+            Expr::GroupingExpression { span, .. } | Expr::LiteralExpression { span, .. } => *span,
+            Expr::InterpolationExpression { parts } => {
+                parts[0].span().to(parts[parts.len() - 1].span())
+            }
+            Expr::SetExpression { object, value, .. } => object.span().to(value.span()),
+            Expr::SuperExpression {
+                keyword, method, ..
+            } => keyword.span.to(method.span),
+            Expr::SelfExpression { keyword, .. } => keyword.span,
+            Expr::UnaryExpression { operator, right } => operator.span.to(right.span()),
+            Expr::VariableResolutionExpression { name, .. } => name.span,
+        };
+    }
 }
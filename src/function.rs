@@ -0,0 +1,69 @@
+use std::fmt::Debug;
+use std::rc::Rc;
+
+use crate::environment::{Env, Environment};
+use crate::value::Value;
+use crate::stmt::Stmt;
+use crate::token::Token;
+
+/// Function is a user-defined function together with the environment it was declared in (its
+/// closure).
+pub struct Function {
+    pub name: Token,
+    pub params: Vec<Token>,
+    /// Shared between the declared function and the methods bound from it.
+    pub body: Rc<Vec<Stmt>>,
+    pub closure: Env,
+    /// Whether this is a class's `init` method. Initializers always return the instance.
+    pub is_initializer: bool,
+}
+
+impl Function {
+    /// The number of arguments the function expects.
+    pub fn arity(&self) -> usize {
+        return self.params.len();
+    }
+
+    /// Returns a copy of this method whose closure has `self` bound to the given instance.
+    pub fn bind(&self, instance: Value) -> Function {
+        let env = Environment::new_enclosed(&self.closure);
+        env.borrow_mut().define("self".into(), instance);
+
+        return Function {
+            name: self.name.clone(),
+            params: self.params.clone(),
+            body: Rc::clone(&self.body),
+            closure: env,
+            is_initializer: self.is_initializer,
+        };
+    }
+}
+
+impl PartialEq for Function {
+    /// Functions are only equal to themselves.
+    fn eq(&self, other: &Self) -> bool {
+        return std::ptr::eq(self, other);
+    }
+}
+
+impl Debug for Function {
+    // The closure is left out on purpose. It can hold the function itself.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "<fn {}>", self.name.lexeme);
+    }
+}
+
+/// NativeFunction is a function built into the interpreter and implemented in Rust.
+pub struct NativeFunction {
+    pub name: &'static str,
+    pub arity: usize,
+    /// Runs the function on the already evaluated arguments. There are always `arity` of them.
+    /// The error is a message the interpreter reports at the call.
+    pub function: fn(&[Value]) -> Result<Value, String>,
+}
+
+impl Debug for NativeFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "<native fn {}>", self.name);
+    }
+}
@@ -1,623 +1,761 @@
-use crate::expressions::Expr;
+use crate::class::{Class, Instance};
+use crate::dialect::Dialect;
+use crate::environment::{Env, Environment};
+use crate::expressions::{Binding, Expr};
+use crate::function::{Function, NativeFunction};
 use crate::language_error::Error;
-use crate::literal::Literal;
-use crate::report_error;
+use crate::operators::{self, truthy_or_falsey, BinaryOperator, UnaryOperator};
+use crate::value::Value;
 use crate::stmt::Stmt;
+use crate::token::Token;
 use crate::token_kinds::TokenKind;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::io::Write;
+use std::rc::Rc;
+
+/// The way a statement finished executing. Anything other than `Normal` unwinds the statements
+/// enclosing it until one of them handles it.
+#[derive(Debug, PartialEq)]
+enum Completion {
+    Normal,
+    /// A `return` statement ran. Handled by the function call that's being executed.
+    Return(Value),
+    /// A `break` statement ran. Handled by the innermost loop, or by the loop with the given label.
+    Break(Option<String>),
+    /// A `continue` statement ran. Handled like `Break`.
+    Continue(Option<String>),
+}
+
+/// Interpreter executes statements against a global environment it keeps between runs. This lets
+/// the REPL see the definitions made by previous lines.
+pub struct Interpreter {
+    globals: Env,
+    dialect: Dialect,
+}
 
-type Env = Vec<HashMap<String, Literal>>;
+impl Default for Interpreter {
+    fn default() -> Self {
+        return Interpreter::new();
+    }
+}
 
-pub fn interpret(statements: &Vec<Stmt>) {
-    let mut env = Env::new();
-    env.push(HashMap::new());
+impl Interpreter {
+    pub fn new() -> Self {
+        return Interpreter::with_dialect(Dialect::default());
+    }
 
-    add_builtin_variables(&mut env[0]);
+    /// Creates an interpreter that runs code with the semantics of the given dialect.
+    pub fn with_dialect(dialect: Dialect) -> Self {
+        let globals = Environment::new_global();
 
-    for statement in statements {
-        let mut do_break = false;
-        execute(Box::new(statement), &mut env).unwrap_or_else(|err| {
-            report_error(&err);
-            do_break = true;
-        });
+        add_builtin_variables(&mut globals.borrow_mut());
 
-        if do_break {
-            break;
-        }
+        return Interpreter { globals, dialect };
     }
-}
 
-fn add_builtin_variables(env: &mut HashMap<String, Literal>) {
-    env.insert("OS".into(), Literal::String((std::env::consts::OS).to_string()));
-}
+    pub fn dialect(&self) -> Dialect {
+        return self.dialect;
+    }
 
-/// Executes the given statement.
-fn execute(stmt: Box<&Stmt>, env: &mut Env) -> Result<(), Error> {
-    return match stmt.as_ref() {
-        Stmt::VarDeclStmt { name, initializer } => {
-            let value = evaluate(initializer, env);
-            if value.is_err() {
-                return Err(value.err().unwrap());
-            }
+    /// Executes the given statements in the global environment. Stops at, and returns, the first
+    /// runtime error.
+    pub fn interpret(&mut self, statements: &Vec<Stmt>) -> Result<(), Error> {
+        for statement in statements {
+            // The parser doesn't allow `break` and `continue` outside of loops, and the resolver
+            // doesn't allow `return` outside of functions.
+            self.execute(statement, &self.globals)?;
+        }
 
-            if get_symbol_in_scope(env, &name.lexeme).is_some() {
-                return Err(Error {
-                    msg: format!("Variable \"{}\" already declared.", name.lexeme),
-                    line: Some(name.line),
-                    column: 0,
-                    hint: None,
-                });
-            }
+        return Ok(());
+    }
 
-            add_symbol_to_current_scope(env, name.clone().lexeme, value.unwrap());
-            return Ok(());
-        }
-        Stmt::AssignmentStmt {
-            // FIX: `a = b = 5;` is not currently allowed.
-            expression,
-        } => {
-            if let Expr::AssignmentExpression { name, value } = expression.as_ref() {
-                if !get_symbol_in_scope(env, &name.lexeme).is_some() {
+    /// Executes the given statement.
+    fn execute(&self, stmt: &Stmt, env: &Env) -> Result<Completion, Error> {
+        return match stmt {
+            Stmt::VarDeclStmt { name, initializer, .. } => {
+                let value = self.evaluate(initializer, env)?;
+
+                if self.dialect == Dialect::Extended && env.borrow().get(&name.lexeme).is_some() {
                     return Err(Error {
-                        msg: format!("Assignment of undeclared variable \"{}\".", name.lexeme),
+                        msg: format!("Variable \"{}\" already declared.", name.lexeme),
                         line: Some(name.line),
-                        column: 0,
+                        column: name.column,
+                        span: Some(name.span),
                         hint: None,
                     });
                 }
 
-                let value = evaluate(value, env);
-                if value.is_err() {
-                    return Err(value.err().unwrap());
+                env.borrow_mut().define(name.lexeme.clone(), value);
+                return Ok(Completion::Normal);
+            }
+            Stmt::AssignmentStmt { expression } => {
+                self.evaluate(expression, env)?;
+
+                return Ok(Completion::Normal);
+            }
+            Stmt::BlockStmt { statements } => {
+                return self.execute_block(statements, &Environment::new_enclosed(env));
+            }
+            Stmt::ClassStmt {
+                name,
+                methods,
+                superclass,
+                ..
+            } => {
+                let superclass = match superclass {
+                    Some(superclass) => match self.evaluate(superclass, env)? {
+                        Value::Class(class) => Some(class),
+                        _ => {
+                            return Err(Error {
+                                msg: "Superclass must be a class.".into(),
+                                line: Some(name.line),
+                                column: name.column,
+                                span: Some(superclass.span()),
+                                hint: None,
+                            });
+                        }
+                    },
+                    None => None,
+                };
+
+                // Methods of a subclass see `super` through an extra scope between them and the class.
+                let methods_env = match &superclass {
+                    Some(superclass) => {
+                        let methods_env = Environment::new_enclosed(env);
+                        methods_env
+                            .borrow_mut()
+                            .define("super".into(), Value::Class(Rc::clone(superclass)));
+
+                        methods_env
+                    }
+                    None => Rc::clone(env),
+                };
+
+                let mut class_methods = HashMap::<String, Rc<Function>>::new();
+                for method in methods {
+                    if let Stmt::FunctionStmt { name, params, body, .. } = method {
+                        let function = Function {
+                            name: name.clone(),
+                            params: params.clone(),
+                            body: Rc::new(body.clone()),
+                            closure: Rc::clone(&methods_env),
+                            is_initializer: name.lexeme == "init",
+                        };
+
+                        class_methods.insert(name.lexeme.clone(), Rc::new(function));
+                    }
                 }
 
-                add_symbol_to_current_scope(env, name.clone().lexeme, value.unwrap());
+                let class = Class {
+                    name: name.lexeme.clone(),
+                    superclass,
+                    methods: class_methods,
+                };
 
-                return Ok(());
-            } else {
-                return Err(Error {
-                    msg: format!("Invalid assignment."),
-                    line: Some(0),
-                    column: 0,
-                    hint: None,
-                });
-            }
-        }
-        Stmt::BlockStmt { statements } => {
-            env.push(HashMap::new());
+                env.borrow_mut().define(name.lexeme.clone(), Value::Class(Rc::new(class)));
 
-            for statement in statements {
-                execute(Box::new(statement), env)?;
+                return Ok(Completion::Normal);
             }
+            Stmt::ExpressionStmt { expression } => {
+                return match self.evaluate(expression, env) {
+                    Ok(_) => Ok(Completion::Normal),
+                    Err(err) => Err(err),
+                };
+            }
+            Stmt::FunctionStmt { name, params, body, .. } => {
+                let function = Function {
+                    name: name.clone(),
+                    params: params.clone(),
+                    body: Rc::new(body.clone()),
+                    closure: Rc::clone(env),
+                    is_initializer: false,
+                };
+
+                env.borrow_mut().define(name.lexeme.clone(), Value::Function(Rc::new(function)));
+
+                return Ok(Completion::Normal);
+            }
+            Stmt::IfStmt {
+                condition,
+                then_branch,
+                else_if_branches,
+                else_branch,
+            } => {
+                let main_if_success = truthy_or_falsey(&self.evaluate(condition, env)?, self.dialect);
+
+                // Here we decide if we want to execute the main `if` branch or any of the `else if`s or the `else`.
+                if main_if_success {
+                    return self.execute(then_branch, env);
+                }
 
-            env.pop();
-
-            return Ok(());
-        }
-        Stmt::ClassStmt {
-            ..
-        } => {
-            todo!()
-        }
-        Stmt::ExpressionStmt { expression } => {
-            return match evaluate(expression, env) {
-                Ok(_) => Ok(()),
-                Err(err) => Err(err),
-            };
-        }
-        Stmt::FunctionStmt { .. } => {
-            todo!()
-        }
-        Stmt::IfStmt {
-            condition,
-            then_branch,
-            else_if_branches,
-            else_branch,
-        } => {
-            let main_if_success = truthy_or_falsey(
-                &evaluate(&condition, env)?,
-                env
-            )?;
-
-            // Here we decide if we want to execute the main `if` branch or any of the `else if`s or the `else`.
-            if main_if_success {
-                execute(Box::new(then_branch), env)?;
-            } else {
-                let mut do_else = true;
                 for else_if_statement in else_if_branches.iter() {
                     if let Stmt::IfStmt { condition: else_if_condition, then_branch: else_if_then_branch, .. } = else_if_statement.as_ref() {
-                        let success = truthy_or_falsey(
-                            &evaluate(else_if_condition, env)?,
-                            env
-                        )?;
+                        let success = truthy_or_falsey(&self.evaluate(else_if_condition, env)?, self.dialect);
                         if success {
-                            execute(Box::new(else_if_then_branch.as_ref()), env)?;
-                            do_else = false;
-                            break;
+                            return self.execute(else_if_then_branch, env);
                         }
                     }
                 }
 
-                if do_else {
-                    if let Some(else_body) = else_branch {
-                        execute(Box::new(*&else_body), env)?;
+                if let Some(else_body) = else_branch {
+                    return self.execute(else_body, env);
+                }
+
+                return Ok(Completion::Normal);
+            }
+            Stmt::PrintStmt { expression } => {
+                let mut stdout = std::io::stdout();
+
+                let value = self.evaluate(expression, env)?;
+
+                /* return */
+                match stdout.write_all(format!("{}\n", stringify(&value, self.dialect)).as_ref()) {
+                    Ok(_) => Ok(Completion::Normal),
+                    Err(_) => {
+                        return Err(Error {
+                            msg: "Error writing to stdout".into(),
+                            line: None,
+                            column: 0,
+                            span: None,
+                            hint: None,
+                        });
                     }
                 }
             }
+            Stmt::ReturnStmt { value, .. } => {
+                let value = match value {
+                    Some(value) => self.evaluate(value, env)?,
+                    None => Value::Nil,
+                };
 
-            return Ok(());
-        }
-        Stmt::PrintStmt { expression } => {
-            let mut stdout = std::io::stdout();
-
-            let value = evaluate(expression, env);
-            if value.is_err() {
-                return Err(value.err().unwrap());
+                return Ok(Completion::Return(value));
             }
+            Stmt::WhileStmt {
+                condition,
+                body,
+                increment,
+                per_iteration_scope,
+                label,
+            } => {
+                // Whether a `break` or `continue` with the given label targets this loop.
+                let targets_this_loop = |target: &Option<String>| match target {
+                    Some(target) => label.as_ref().is_some_and(|label| &label.lexeme == target),
+                    None => true,
+                };
+
+                let mut env = Rc::clone(env);
+
+                while truthy_or_falsey(&self.evaluate(condition, &env)?, self.dialect) {
+                    // The body is a block, so every iteration runs in a fresh scope.
+                    match self.execute(body, &env)? {
+                        Completion::Normal => (),
+                        Completion::Break(target) if targets_this_loop(&target) => break,
+                        Completion::Continue(target) if targets_this_loop(&target) => (),
+                        completion => return Ok(completion),
+                    }
 
-            /* return */
-            match stdout.write(format!("{}\n", value.unwrap().to_string()).as_ref()) {
-                Ok(_) => Ok(()),
-                Err(_) => {
-                    return Err(Error {
-                        msg: format!("Error writing to stdout"),
-                        line: None,
-                        column: 0,
-                        hint: None,
-                    });
+                    // The next iteration, starting with the increment, works on a copy of the loop
+                    // variables. The closures the body made keep the ones of this iteration.
+                    if *per_iteration_scope {
+                        env = Environment::new_copy(&env);
+                    }
+
+                    if let Some(increment) = increment {
+                        self.execute(increment, &env)?;
+                    }
                 }
+
+                return Ok(Completion::Normal);
+            }
+            Stmt::BreakStmt { label, .. } => {
+                return Ok(Completion::Break(label.as_ref().map(|label| label.lexeme.clone())));
+            }
+            Stmt::ContinueStmt { label, .. } => {
+                return Ok(Completion::Continue(label.as_ref().map(|label| label.lexeme.clone())));
+            }
+        };
+    }
+
+    /// Executes the given statements in the given environment. Stops at the first statement that
+    /// doesn't complete normally and hands its completion to the caller.
+    fn execute_block(&self, statements: &Vec<Stmt>, env: &Env) -> Result<Completion, Error> {
+        for statement in statements {
+            let completion = self.execute(statement, env)?;
+
+            if completion != Completion::Normal {
+                return Ok(completion);
             }
         }
-        Stmt::ReturnStmt { .. } => {
-            todo!()
-        }
-        Stmt::WhileStmt { .. } => {
-            todo!()
-        }
-        Stmt::None { err } => {
+
+        return Ok(Completion::Normal);
+    }
+
+    /// Calls the given callee with the already evaluated arguments.
+    fn call(&self, callee: Value, arguments: Vec<Value>, paren: &Token) -> Result<Value, Error> {
+        let arity = match &callee {
+            Value::Function(function) => function.arity(),
+            Value::NativeFunction(function) => function.arity,
+            Value::Class(class) => class.arity(),
+            _ => {
+                return Err(Error {
+                    msg: "Can only call functions and classes.".into(),
+                    line: Some(paren.line),
+                    column: paren.column,
+                    span: Some(paren.span),
+                    hint: None,
+                });
+            }
+        };
+
+        if arguments.len() != arity {
             return Err(Error {
-                msg: err.to_owned(),
-                line: None,
-                column: 0,
+                msg: format!("Expected {} arguments but got {}.", arity, arguments.len()),
+                line: Some(paren.line),
+                column: paren.column,
+                span: Some(paren.span),
                 hint: None,
             });
         }
-    };
-}
 
-/// Evaluates the given expression.
-fn evaluate(expr: &Expr, env: &mut Env) -> Result<Literal, Error> {
-    match expr {
-        Expr::AssignmentExpression { name: _name, value } => {
-            return evaluate(value, env);
-        }
-        Expr::BinaryExpression {
-            left,
-            operator,
-            right,
-        } => {
-            let left = evaluate(left, env);
-            let right = evaluate(right, env);
-
-            return match operator.kind {
-                TokenKind::Plus => match left {
-                    Ok(Literal::Number(left)) => match right {
-                        Ok(Literal::Number(right)) => Ok(Literal::Number(left + right)),
-                        Ok(Literal::String(right)) => {
-                            Ok(Literal::String(left.to_string() + &right))
-                        }
-                        Err(err) => Err(err),
-                        _ => Err(Error {
-                            msg: format!(
-                                "Operands of \"{}\" must be two numbers or two strings.",
-                                &operator.lexeme
-                            ),
-                            line: Some(operator.line),
-                            column: 0,
-                            hint: None,
-                        }),
-                    },
-                    Ok(Literal::String(left)) => match right {
-                        Ok(Literal::Number(right)) => {
-                            Ok(Literal::String(left + &right.to_string()))
-                        }
-                        Ok(Literal::String(right)) => Ok(Literal::String(left + &right)),
-                        Err(err) => Err(err),
-                        _ => Err(Error {
-                            msg: format!(
-                                "Operands of \"{}\" must be two numbers or two strings.",
-                                &operator.lexeme
-                            ),
-                            line: Some(operator.line),
-                            column: 0,
-                            hint: None,
-                        }),
-                    },
-                    Err(err) => Err(err),
-                    _ => Err(Error {
-                        msg: format!(
-                            "Operands of \"{}\" must be two numbers or two strings.",
-                            &operator.lexeme
-                        ),
-                        line: Some(operator.line),
-                        column: 0,
-                        hint: None,
-                    }),
-                },
-                TokenKind::Minus | TokenKind::Star | TokenKind::Slash => {
-                    match left {
-                        Ok(Literal::Number(left)) => {
-                            match right {
-                                Ok(Literal::Number(right)) => {
-                                    match operator.kind {
-                                        TokenKind::Minus => {
-                                            return Ok(Literal::Number(left - right));
-                                        }
-                                        TokenKind::Star => {
-                                            return Ok(Literal::Number(left * right));
-                                        }
-                                        TokenKind::Slash => {
-                                            if right == 0f64 {
-                                                return Err(Error {
-                                                    msg: "Cannot divide by zero.".into(),
-                                                    line: Some(operator.line),
-                                                    column: 0,
-                                                    hint: None,
-                                                });
-                                            }
-
-                                            return Ok(Literal::Number(left / right));
-                                        }
-                                        _ => Ok(Literal::Number(0f64)), // This should never happen.
-                                    }
-                                }
-                                Err(err) => Err(err),
-                                _ => Err(Error {
-                                    msg: format!(
-                                        "Operands of \"{}\" must be two numbers.",
-                                        &operator.lexeme
-                                    ),
-                                    line: Some(operator.line),
-                                    column: 0,
-                                    hint: None,
-                                }),
-                            }
-                        }
-                        Err(err) => Err(err),
-                        _ => Err(Error {
-                            msg: format!(
-                                "Operands of \"{}\" must be two numbers.",
-                                &operator.lexeme
-                            ),
-                            line: Some(operator.line),
-                            column: 0,
-                            hint: None,
-                        }),
-                    }
-                }
-                TokenKind::BangEqual | TokenKind::EqualEqual => {
-                    return match left {
-                        Ok(Literal::Number(left)) => {
-                            match right {
-                                Ok(Literal::Number(right)) => {
-                                    Ok(Literal::Boolean(match operator.kind {
-                                        TokenKind::BangEqual => left != right,
-                                        TokenKind::EqualEqual => left == right,
-                                        _ => false, // This should never happen.
-                                    }))
-                                }
-                                Ok(Literal::String(_right)) => Ok(Literal::Boolean(false)),
-                                Ok(Literal::Boolean(_right)) => Ok(Literal::Boolean(false)),
-                                Ok(Literal::Nil) => {
-                                    Ok(Literal::Boolean(match operator.kind {
-                                        TokenKind::BangEqual => true,
-                                        TokenKind::EqualEqual => false,
-                                        _ => false, // This should never happen.
-                                    }))
-                                }
-                                Err(err) => Err(err),
-                            }
-                        }
-                        Err(err) => Err(err),
-                        Ok(Literal::String(left)) => {
-                            match right {
-                                Ok(Literal::String(right)) => {
-                                    Ok(Literal::Boolean(match operator.kind {
-                                        TokenKind::BangEqual => left != right,
-                                        TokenKind::EqualEqual => left == right,
-                                        _ => false, // This should never happen.
-                                    }))
-                                }
-                                Ok(Literal::Number(_right)) => Ok(Literal::Boolean(false)),
-                                Ok(Literal::Boolean(_right)) => Ok(Literal::Boolean(false)),
-                                Ok(Literal::Nil) => {
-                                    Ok(Literal::Boolean(match operator.kind {
-                                        TokenKind::BangEqual => true,
-                                        TokenKind::EqualEqual => false,
-                                        _ => false, // This should never happen.
-                                    }))
-                                }
-                                Err(err) => Err(err),
-                            }
-                        }
-                        Ok(Literal::Boolean(left)) => {
-                            match right {
-                                Ok(Literal::Boolean(right)) => {
-                                    Ok(Literal::Boolean(match operator.kind {
-                                        TokenKind::BangEqual => left != right,
-                                        TokenKind::EqualEqual => left == right,
-                                        _ => false, // This should never happen.
-                                    }))
-                                }
-                                Ok(Literal::Number(_right)) => Ok(Literal::Boolean(false)),
-                                Ok(Literal::String(_right)) => Ok(Literal::Boolean(false)),
-                                Ok(Literal::Nil) => Ok(Literal::Boolean(false)),
-                                Err(err) => Err(err),
-                            }
-                        }
-                        Ok(Literal::Nil) => {
-                            match right {
-                                Ok(Literal::Nil) => {
-                                    Ok(Literal::Boolean(match operator.kind {
-                                        TokenKind::BangEqual => false,
-                                        TokenKind::EqualEqual => true,
-                                        _ => false, // This should never happen.
-                                    }))
-                                }
-                                Ok(Literal::Number(_right)) => {
-                                    Ok(Literal::Boolean(match operator.kind {
-                                        TokenKind::BangEqual => true,
-                                        TokenKind::EqualEqual => false,
-                                        _ => false, // This should never happen.
-                                    }))
-                                }
-                                Ok(Literal::String(_right)) => {
-                                    Ok(Literal::Boolean(match operator.kind {
-                                        TokenKind::BangEqual => true,
-                                        TokenKind::EqualEqual => false,
-                                        _ => false, // This should never happen.
-                                    }))
-                                }
-                                Ok(Literal::Boolean(_right)) => {
-                                    Ok(Literal::Boolean(match operator.kind {
-                                        TokenKind::BangEqual => false,
-                                        TokenKind::EqualEqual => false,
-                                        _ => false, // This should never happen.
-                                    }))
-                                }
-                                Err(err) => Err(err),
-                            }
-                        }
-                    };
-                }
-                TokenKind::Greater
-                | TokenKind::GreaterEqual
-                | TokenKind::Less
-                | TokenKind::LessEqual => {
-                    return match left {
-                        Ok(Literal::Number(left)) => {
-                            match right {
-                                Ok(Literal::Number(right)) => {
-                                    Ok(Literal::Boolean(match operator.kind {
-                                        TokenKind::Greater => left > right,
-                                        TokenKind::GreaterEqual => left >= right,
-                                        TokenKind::Less => left < right,
-                                        TokenKind::LessEqual => left <= right,
-                                        _ => false, // This should never happen.
-                                    }))
-                                }
-                                Err(err) => Err(err),
-                                _ => Err(Error {
-                                    msg: format!(
-                                        "Operands of \"{}\" must be two numbers.",
-                                        &operator.lexeme
-                                    ),
-                                    line: Some(operator.line),
-                                    column: 0,
-                                    hint: None,
-                                }),
-                            }
-                        }
-                        Err(err) => Err(err),
-                        _ => Err(Error {
-                            msg: format!(
-                                "Operands of \"{}\" must be two numbers.",
-                                &operator.lexeme
-                            ),
-                            line: Some(operator.line),
-                            column: 0,
-                            hint: None,
-                        }),
-                    };
-                }
-                _ => todo!("Handle error"),
-            };
-        }
-        Expr::VariableResolutionExpression { name } => {
-            return match get_symbol_in_scope(env, &name.lexeme) {
-                Some(value) => Ok(value.clone()),
-                None => {
-                    return Err(Error {
-                        msg: format!("Usage of undeclared variable \"{}\".", name.lexeme),
-                        line: Some(name.line),
-                        column: 0,
-                        hint: None,
-                    });
+        return match callee {
+            Value::Function(function) => self.call_function(&function, arguments),
+            Value::NativeFunction(function) => (function.function)(&arguments).map_err(|msg| Error {
+                msg,
+                line: Some(paren.line),
+                column: paren.column,
+                span: Some(paren.span),
+                hint: None,
+            }),
+            Value::Class(class) => {
+                let instance = Value::Instance(Rc::new(RefCell::new(Instance::new(Rc::clone(&class)))));
+
+                if let Some(initializer) = class.find_method("init") {
+                    self.call_function(&initializer.bind(instance.clone()), arguments)?;
                 }
-            };
-        }
-        Expr::CallExpression {
-            ..
-        } => {
-            todo!();
-        }
-        Expr::GetExpression { .. } => {
-            todo!();
-        }
-        Expr::GroupingExpression { expression } => {
-            return evaluate(expression, env);
+
+                Ok(instance)
+            }
+            _ => Ok(Value::Nil), // This should never happen.
+        };
+    }
+
+    /// Runs the body of the given function with its parameters bound to the given arguments.
+    fn call_function(&self, function: &Function, arguments: Vec<Value>) -> Result<Value, Error> {
+        let call_env = Environment::new_enclosed(&function.closure);
+        for (param, argument) in function.params.iter().zip(arguments) {
+            call_env.borrow_mut().define(param.lexeme.clone(), argument);
         }
-        Expr::LiteralExpression { value } => {
-            return match value {
-                Some(value) => Ok(value.clone()),
-                None => Ok(Literal::Nil),
-            };
+
+        let value = match self.execute_block(&function.body, &call_env)? {
+            Completion::Return(value) => value,
+            // The parser doesn't allow `break` and `continue` to escape a function.
+            _ => Value::Nil,
+        };
+
+        // Initializers hand back the instance they initialized, even on an early `return;`.
+        if function.is_initializer {
+            return Ok(Environment::get_at(&function.closure, 0, 0).unwrap_or(Value::Nil));
         }
-        Expr::LogicalExpression {
-            left,
-            operator,
-            right
-        } => {
 
-            let left_val = evaluate(left, env)?;
-            let right_val = evaluate(right, env)?;
+        return Ok(value);
+    }
 
-            if operator.kind == TokenKind::Or {
-                if truthy_or_falsey(&left_val, env)? {
-                    return Ok(left_val);
-                } else if truthy_or_falsey(&right_val, env)? {
-                    return Ok(right_val);
-                } else {
-                    return Ok(Literal::Boolean(false));
-                }
-            } else if operator.kind == TokenKind::And {
-                if truthy_or_falsey(&left_val, env)? && truthy_or_falsey(&right_val, env)? {
-                    return Ok(right_val);
-                } else {
-                    return Ok(Literal::Boolean(false));
-                }
+    /// Evaluates the given expression.
+    fn evaluate(&self, expr: &Expr, env: &Env) -> Result<Value, Error> {
+        match expr {
+            Expr::AssignmentExpression {
+                name,
+                value,
+                binding,
+            } => {
+                let value = self.evaluate(value, env)?;
+
+                self.assign_variable(name, binding, value.clone(), env)?;
+
+                // Assignment is an expression, so `a = b = 5;` assigns 5 to both.
+                return Ok(value);
             }
+            Expr::CompoundAssignmentExpression {
+                target,
+                operator,
+                value,
+                postfix,
+            } => {
+                // The current value is read before the right side runs, and the parts of the target
+                // are only evaluated once.
+                return match target.as_ref() {
+                    Expr::VariableResolutionExpression { name, binding } => {
+                        let current = self.evaluate(target, env)?;
+                        let result = self.compound_assignment(current.clone(), operator, value, env)?;
+
+                        self.assign_variable(name, binding, result.clone(), env)?;
+
+                        Ok(if *postfix { current } else { result })
+                    }
+                    Expr::GetExpression { object, name } => {
+                        let instance = self.evaluate_instance(object, name, env)?;
+                        let current = self.get_property(&instance, name)?;
+                        let result = self.compound_assignment(current.clone(), operator, value, env)?;
 
-            return Ok(Literal::Nil);
-        }
-        Expr::SetExpression {
-            ..
-        } => {
-            todo!();
-        }
-        Expr::SuperExpression { .. } => {
-            todo!();
-        }
-        Expr::SelfExpression { .. } => {
-            todo!();
-        }
-        Expr::UnaryExpression { operator, right } => {
-            let interpreted_right = evaluate(right, env);
+                        instance.borrow_mut().set(name.lexeme.clone(), result.clone());
 
-            return match operator.kind {
-                TokenKind::Minus => match interpreted_right {
-                    Ok(Literal::Number(right)) => Ok(Literal::Number(-right)),
-                    Err(err) => Err(err),
+                        Ok(if *postfix { current } else { result })
+                    }
+                    // The parser only makes compound assignments to the targets above.
+                    _ => Err(operator_error("Invalid assignment target.".into(), operator)),
+                };
+            }
+            Expr::BinaryExpression {
+                left,
+                operator,
+                right,
+            } => {
+                let left = self.evaluate(left, env)?;
+                let right = self.evaluate(right, env)?;
+
+                // The parser only makes binary expressions out of binary operators.
+                let Some(kind) = BinaryOperator::from_token(&operator.kind) else {
+                    return Err(operator_error(format!("Unknown binary operator \"{}\".", operator.lexeme), operator));
+                };
+
+                return operators::binary(kind, left, right, self.dialect).map_err(|msg| operator_error(msg, operator));
+            }
+            Expr::VariableResolutionExpression { name, binding } => {
+                let value = match binding {
+                    Some(binding) => Environment::get_at(env, binding.depth, binding.slot),
+                    None => env.borrow().get(&name.lexeme),
+                };
+
+                return match value {
+                    Some(value) => Ok(value),
+                    None => {
+                        return Err(Error {
+                            msg: format!("Usage of undeclared variable \"{}\".", name.lexeme),
+                            line: Some(name.line),
+                            column: name.column,
+                            span: Some(name.span),
+                            hint: None,
+                        });
+                    }
+                };
+            }
+            Expr::CallExpression {
+                callee,
+                paren,
+                arguments,
+            } => {
+                let callee = self.evaluate(callee, env)?;
+
+                let mut evaluated_arguments = Vec::<Value>::with_capacity(arguments.len());
+                for argument in arguments {
+                    evaluated_arguments.push(self.evaluate(argument, env)?);
+                }
+
+                return self.call(callee, evaluated_arguments, paren);
+            }
+            Expr::ConditionalExpression {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                return if truthy_or_falsey(&self.evaluate(condition, env)?, self.dialect) {
+                    self.evaluate(then_branch, env)
+                } else {
+                    self.evaluate(else_branch, env)
+                };
+            }
+            Expr::GetExpression { object, name } => {
+                return match self.evaluate(object, env)? {
+                    Value::Instance(instance) => self.get_property(&instance, name),
                     _ => Err(Error {
-                        msg: format!("Operand of \"{}\" must be a number.", &operator.lexeme),
-                        line: Some(operator.line),
-                        column: 0,
+                        msg: "Only instances have properties.".into(),
+                        line: Some(name.line),
+                        column: name.column,
+                        span: Some(name.span),
                         hint: None,
                     }),
-                },
-                TokenKind::Bang => match interpreted_right {
-                    Ok(literal) => {
-                        return match literal {
-                            Literal::Boolean(value) => Ok(Literal::Boolean(!value)),
-                            Literal::Number(value) => {
-                                return Ok(
-                                    Literal::Boolean(
-                                        !truthy_or_falsey(
-                                            &evaluate(&Box::new(Expr::LiteralExpression { value: Some(Literal::Number(value)) }), env)?, env
-                                        )?
-                                    )
-                                );
-                            }
-                            Literal::String(value) => {
-                                return Ok(
-                                    Literal::Boolean(
-                                        !truthy_or_falsey(
-                                            &evaluate(&Box::new(Expr::LiteralExpression { value: Some(Literal::String(value)) }), env)?, env
-                                        )?
-                                    )
-                                );
-                            }
-                            Literal::Nil => Ok(Literal::Boolean(!false)) // Hard coding this because it doesn't matter.
+                };
+            }
+            Expr::GroupingExpression { expression, .. } => {
+                return self.evaluate(expression, env);
+            }
+            Expr::InterpolationExpression { parts } => {
+                let mut ret = String::new();
+
+                for part in parts {
+                    ret += stringify(&self.evaluate(part, env)?, self.dialect).as_str();
+                }
+
+                return Ok(Value::String(ret));
+            }
+            Expr::LiteralExpression { value, .. } => {
+                return match value {
+                    Some(value) => Ok(Value::from(value.clone())),
+                    None => Ok(Value::Nil),
+                };
+            }
+            Expr::LogicalExpression {
+                left,
+                operator,
+                right,
+            } => {
+                let left = self.evaluate(left, env)?;
+
+                // The right operand only runs when the left one doesn't decide the result already.
+                // Either way, the result is the deciding operand itself.
+                let left_decides = match operator.kind {
+                    TokenKind::Or => truthy_or_falsey(&left, self.dialect),
+                    _ => !truthy_or_falsey(&left, self.dialect),
+                };
+
+                if left_decides {
+                    return Ok(left);
+                }
+
+                return self.evaluate(right, env);
+            }
+            Expr::SetExpression {
+                object,
+                name,
+                value,
+            } => {
+                let instance = self.evaluate_instance(object, name, env)?;
+
+                let value = self.evaluate(value, env)?;
+                instance.borrow_mut().set(name.lexeme.clone(), value.clone());
+
+                return Ok(value);
+            }
+            Expr::SuperExpression {
+                keyword,
+                method,
+                binding,
+            } => {
+                // `self` lives in the scope right inside the one holding `super`.
+                let (superclass, instance) = match binding {
+                    Some(binding) => (
+                        Environment::get_at(env, binding.depth, binding.slot),
+                        Environment::get_at(env, binding.depth - 1, 0),
+                    ),
+                    None => (None, None),
+                };
+
+                return match (superclass, instance) {
+                    (Some(Value::Class(superclass)), Some(instance)) => {
+                        match superclass.find_method(&method.lexeme) {
+                            Some(found) => Ok(Value::Function(Rc::new(found.bind(instance)))),
+                            None => Err(Error {
+                                msg: format!("Undefined property \"{}\".", method.lexeme),
+                                line: Some(method.line),
+                                column: method.column,
+                                span: Some(method.span),
+                                hint: None,
+                            }),
                         }
                     }
-                    Err(err) => Err(err),
                     _ => Err(Error {
-                        msg: format!("Operand of \"{}\" must be a boolean.", &operator.lexeme),
-                        line: Some(operator.line),
-                        column: 0,
+                        msg: "Can't use \"super\" outside of a subclass method.".into(),
+                        line: Some(keyword.line),
+                        column: keyword.column,
+                        span: Some(keyword.span),
                         hint: None,
                     }),
-                },
-                _ => todo!("Handle error"),
-            };
-        }
-        Expr::VarDeclExpression { .. } => {
-            todo!();
+                };
+            }
+            Expr::SelfExpression { keyword, binding } => {
+                let instance = match binding {
+                    Some(binding) => Environment::get_at(env, binding.depth, binding.slot),
+                    None => None,
+                };
+
+                return match instance {
+                    Some(value) => Ok(value),
+                    None => Err(Error {
+                        msg: "Can't use \"self\" outside of a class method.".into(),
+                        line: Some(keyword.line),
+                        column: keyword.column,
+                        span: Some(keyword.span),
+                        hint: None,
+                    }),
+                };
+            }
+            Expr::UnaryExpression { operator, right } => {
+                let right = self.evaluate(right, env)?;
+
+                let Some(kind) = UnaryOperator::from_token(&operator.kind) else {
+                    return Err(operator_error(format!("Unknown unary operator \"{}\".", operator.lexeme), operator));
+                };
+
+                return operators::unary(kind, right, self.dialect).map_err(|msg| operator_error(msg, operator));
+            }
         }
     }
-}
 
-/// Evaluates the given variable name.
-fn get_symbol_in_scope<'a>(env: &'a Env, name: &'a String) -> Option<&'a Literal> {
-    let mut i = if env.len() == 0 { 0 } else { env.len() - 1 };
-    while i >= 0 {
-        if env[i].contains_key(name) {
-            return env[i].get(name);
+    /// Evaluates `value` and applies the operator of a compound assignment to the target's current
+    /// value and it. `++` and `--` only take numbers, or they'd concatenate a string and 1.
+    fn compound_assignment(&self, current: Value, operator: &Token, value: &Expr, env: &Env) -> Result<Value, Error> {
+        let Some(kind) = BinaryOperator::from_compound_assignment(&operator.kind) else {
+            return Err(operator_error(format!("Unknown assignment operator \"{}\".", operator.lexeme), operator));
+        };
+
+        let increment = matches!(operator.kind, TokenKind::PlusPlus | TokenKind::MinusMinus);
+        if increment && !matches!(current, Value::Number(_)) {
+            return Err(operator_error(format!("Operand of \"{}\" must be a number.", operator.lexeme), operator));
         }
 
-        if i == 0 {
-            break;
+        let value = self.evaluate(value, env)?;
+
+        return operators::binary(kind, current, value, self.dialect).map_err(|msg| operator_error(msg, operator));
+    }
+
+    /// Updates the given variable in the scope that declared it, not in the current one.
+    fn assign_variable(&self, name: &Token, binding: &Option<Binding>, value: Value, env: &Env) -> Result<(), Error> {
+        let assigned = match binding {
+            Some(binding) => Environment::assign_at(env, binding.depth, binding.slot, value),
+            None => env.borrow_mut().assign(&name.lexeme, value),
+        };
+
+        if !assigned {
+            return Err(Error {
+                msg: format!("Assignment of undeclared variable \"{}\".", name.lexeme),
+                line: Some(name.line),
+                column: name.column,
+                span: Some(name.span),
+                hint: None,
+            });
         }
 
-        i -= 1;
+        return Ok(());
     }
 
-    return None;
-}
+    /// Evaluates the object of a field assignment, which has to be an instance.
+    fn evaluate_instance(&self, object: &Expr, name: &Token, env: &Env) -> Result<Rc<RefCell<Instance>>, Error> {
+        return match self.evaluate(object, env)? {
+            Value::Instance(instance) => Ok(instance),
+            _ => Err(Error {
+                msg: "Only instances have fields.".into(),
+                line: Some(name.line),
+                column: name.column,
+                span: Some(name.span),
+                hint: None,
+            }),
+        };
+    }
 
-/// Adds the given symbol to the current scope.
-fn add_symbol_to_current_scope(env: &mut Env, name: String, value: Literal) {
-    let i = env.len() - 1;
-    env[i].insert(name, value);
-}
+    /// Looks up a property on the given instance.
+    fn get_property(&self, instance: &Rc<RefCell<Instance>>, name: &Token) -> Result<Value, Error> {
+        return match Instance::get(instance, &name.lexeme) {
+            Some(value) => Ok(value),
+            None => Err(Error {
+                msg: format!("Undefined property \"{}\".", name.lexeme),
+                line: Some(name.line),
+                column: name.column,
+                span: Some(name.span),
+                hint: None,
+            }),
+        };
+    }
 
-fn truthy_or_falsey(condition: &Literal, env: &mut Env) -> Result<bool, Error> {
-    let ret;
+}
 
-    match condition {
-        Literal::Number(val) => {
-            if *val == 0f64 {
-                ret = false;
-            } else {
-                ret = true;
-            }
-        }
-        Literal::String(val) => {
-            if *val == "".to_string() {
-                ret = false;
+/// Turns the given value into the text `print` shows for it.
+fn stringify(value: &Value, dialect: Dialect) -> String {
+    return match value {
+        // The reference implementation prints numbers the way Java does, minus a trailing ".0".
+        Value::Number(number) if dialect == Dialect::Reference => {
+            if number.is_nan() {
+                "NaN".into()
+            } else if number.is_infinite() {
+                if number.is_sign_positive() { "Infinity".into() } else { "-Infinity".into() }
             } else {
-                ret = true;
+                number.to_string()
             }
         }
-        Literal::Boolean(val) => {
-            ret = *val;
-        }
-        Literal::Nil => {
-            ret = false;
-        }
+        _ => value.to_string(),
+    };
+}
+
+fn add_builtin_variables(env: &mut Environment) {
+    env.define("OS".into(), Value::String((std::env::consts::OS).to_string()));
+
+    let natives = [
+        NativeFunction { name: "clock", arity: 0, function: native_clock },
+        NativeFunction { name: "list", arity: 2, function: native_list },
+    ];
+
+    for native in natives {
+        env.define(native.name.into(), Value::NativeFunction(Rc::new(native)));
     }
+}
+
+/// `clock()` returns the number of seconds since the Unix epoch.
+fn native_clock(_arguments: &[Value]) -> Result<Value, String> {
+    return match std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+        Ok(elapsed) => Ok(Value::Number(elapsed.as_secs_f64())),
+        Err(_) => Err("The system clock is set before the Unix epoch.".into()),
+    };
+}
+
+/// `list(length, value)` returns a new list that holds `value` `length` times.
+fn native_list(arguments: &[Value]) -> Result<Value, String> {
+    return match &arguments[0] {
+        Value::Number(length) if *length >= 0f64 && length.fract() == 0f64 => {
+            Ok(Value::List(Rc::new(RefCell::new(vec![arguments[1].clone(); *length as usize]))))
+        }
+        _ => Err("\"list\" expects a whole number of at least 0 as its length.".into()),
+    };
+}
 
-    return Ok(ret);
+/// Turns the message of an operator that failed into an error pointing at the operator.
+fn operator_error(msg: String, operator: &Token) -> Error {
+    return Error {
+        msg,
+        line: Some(operator.line),
+        column: operator.column,
+        span: Some(operator.span),
+        hint: None,
+    };
 }
 
 
 #[cfg(test)]
 mod tests {
+    use crate::literal::Literal;
+    use crate::parser::Parser;
+    use crate::resolver::Resolver;
+    use crate::span::Span;
     use crate::token::Token;
+    use crate::tokenizer::Tokenizer;
 
     use super::*;
 
+    /// Runs the given source code and returns the global environment it ran in.
+    fn run(source: &str) -> Result<Env, Error> {
+        return run_with_dialect(source, Dialect::Extended);
+    }
+
+    fn run_with_dialect(source: &str, dialect: Dialect) -> Result<Env, Error> {
+        let mut tokenizer = Tokenizer::new(source);
+        let (tokens, _) = tokenizer.scan_tokens();
+
+        let mut parser = Parser::new(tokens);
+        let (mut statements, errors) = parser.parse();
+        if let Some(err) = errors.into_iter().next() {
+            return Err(err);
+        }
+
+        let errors = Resolver::with_dialect(dialect).resolve(&mut statements);
+        if let Some(err) = errors.into_iter().next() {
+            return Err(err);
+        }
+
+        let interpreter = Interpreter::with_dialect(dialect);
+        interpreter.execute_block(&statements, &interpreter.globals)?;
+
+        return Ok(Rc::clone(&interpreter.globals));
+    }
+
     mod evaluate_expressions_tests {
         use super::*;
 
@@ -626,6 +764,7 @@ mod tests {
             let expr = Expr::BinaryExpression {
                 left: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(1.into())),
+                    span: Span::default(),
                 }),
                 operator: Token {
                     kind: TokenKind::Plus,
@@ -633,20 +772,26 @@ mod tests {
                     line: 0,
                     column: 0,
                     literal: None,
+                    span: Span::default(),
+                    doc: None,
+                    leading_trivia: Vec::new(),
+                    trailing_trivia: Vec::new(),
                 },
                 right: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(2.into())),
+                    span: Span::default(),
                 }),
             };
 
             assert_eq!(
-                evaluate(&expr, &mut Env::new()).unwrap(),
-                Literal::Number(3.into())
+                Interpreter::new().evaluate(&expr, &Environment::new_global()).unwrap(),
+                Value::Number(3.into())
             );
 
             let expr = Expr::BinaryExpression {
                 left: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(1.into())),
+                    span: Span::default(),
                 }),
                 operator: Token {
                     kind: TokenKind::Minus,
@@ -654,20 +799,26 @@ mod tests {
                     line: 0,
                     column: 0,
                     literal: None,
+                    span: Span::default(),
+                    doc: None,
+                    leading_trivia: Vec::new(),
+                    trailing_trivia: Vec::new(),
                 },
                 right: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(2.into())),
+                    span: Span::default(),
                 }),
             };
 
             assert_eq!(
-                evaluate(&expr, &mut Env::new()).unwrap(),
-                Literal::Number((-1).into())
+                Interpreter::new().evaluate(&expr, &Environment::new_global()).unwrap(),
+                Value::Number((-1).into())
             );
 
             let expr = Expr::BinaryExpression {
                 left: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(10.into())),
+                    span: Span::default(),
                 }),
                 operator: Token {
                     kind: TokenKind::Star,
@@ -675,20 +826,26 @@ mod tests {
                     line: 0,
                     column: 0,
                     literal: None,
+                    span: Span::default(),
+                    doc: None,
+                    leading_trivia: Vec::new(),
+                    trailing_trivia: Vec::new(),
                 },
                 right: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(2.into())),
+                    span: Span::default(),
                 }),
             };
 
             assert_eq!(
-                evaluate(&expr, &mut Env::new()).unwrap(),
-                Literal::Number(20.into())
+                Interpreter::new().evaluate(&expr, &Environment::new_global()).unwrap(),
+                Value::Number(20.into())
             );
 
             let expr = Expr::BinaryExpression {
                 left: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(10.into())),
+                    span: Span::default(),
                 }),
                 operator: Token {
                     kind: TokenKind::Slash,
@@ -696,20 +853,26 @@ mod tests {
                     line: 0,
                     column: 0,
                     literal: None,
+                    span: Span::default(),
+                    doc: None,
+                    leading_trivia: Vec::new(),
+                    trailing_trivia: Vec::new(),
                 },
                 right: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(2.into())),
+                    span: Span::default(),
                 }),
             };
 
             assert_eq!(
-                evaluate(&expr, &mut Env::new()).unwrap(),
-                Literal::Number(5.into())
+                Interpreter::new().evaluate(&expr, &Environment::new_global()).unwrap(),
+                Value::Number(5.into())
             );
 
             let expr = Expr::BinaryExpression {
                 left: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(10.into())),
+                    span: Span::default(),
                 }),
                 operator: Token {
                     kind: TokenKind::Greater,
@@ -717,20 +880,26 @@ mod tests {
                     line: 0,
                     column: 0,
                     literal: None,
+                    span: Span::default(),
+                    doc: None,
+                    leading_trivia: Vec::new(),
+                    trailing_trivia: Vec::new(),
                 },
                 right: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(2.into())),
+                    span: Span::default(),
                 }),
             };
 
             assert_eq!(
-                evaluate(&expr, &mut Env::new()).unwrap(),
-                Literal::Boolean(true)
+                Interpreter::new().evaluate(&expr, &Environment::new_global()).unwrap(),
+                Value::Boolean(true)
             );
 
             let expr = Expr::BinaryExpression {
                 left: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(10.into())),
+                    span: Span::default(),
                 }),
                 operator: Token {
                     kind: TokenKind::GreaterEqual,
@@ -738,20 +907,26 @@ mod tests {
                     line: 0,
                     column: 0,
                     literal: None,
+                    span: Span::default(),
+                    doc: None,
+                    leading_trivia: Vec::new(),
+                    trailing_trivia: Vec::new(),
                 },
                 right: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(2.into())),
+                    span: Span::default(),
                 }),
             };
 
             assert_eq!(
-                evaluate(&expr, &mut Env::new()).unwrap(),
-                Literal::Boolean(true)
+                Interpreter::new().evaluate(&expr, &Environment::new_global()).unwrap(),
+                Value::Boolean(true)
             );
 
             let expr = Expr::BinaryExpression {
                 left: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(10.into())),
+                    span: Span::default(),
                 }),
                 operator: Token {
                     kind: TokenKind::Less,
@@ -759,20 +934,26 @@ mod tests {
                     line: 0,
                     column: 0,
                     literal: None,
+                    span: Span::default(),
+                    doc: None,
+                    leading_trivia: Vec::new(),
+                    trailing_trivia: Vec::new(),
                 },
                 right: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(2.into())),
+                    span: Span::default(),
                 }),
             };
 
             assert_eq!(
-                evaluate(&expr, &mut Env::new()).unwrap(),
-                Literal::Boolean(false)
+                Interpreter::new().evaluate(&expr, &Environment::new_global()).unwrap(),
+                Value::Boolean(false)
             );
 
             let expr = Expr::BinaryExpression {
                 left: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(10.into())),
+                    span: Span::default(),
                 }),
                 operator: Token {
                     kind: TokenKind::LessEqual,
@@ -780,20 +961,26 @@ mod tests {
                     line: 0,
                     column: 0,
                     literal: None,
+                    span: Span::default(),
+                    doc: None,
+                    leading_trivia: Vec::new(),
+                    trailing_trivia: Vec::new(),
                 },
                 right: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(2.into())),
+                    span: Span::default(),
                 }),
             };
 
             assert_eq!(
-                evaluate(&expr, &mut Env::new()).unwrap(),
-                Literal::Boolean(false)
+                Interpreter::new().evaluate(&expr, &Environment::new_global()).unwrap(),
+                Value::Boolean(false)
             );
 
             let expr = Expr::BinaryExpression {
                 left: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(10.into())),
+                    span: Span::default(),
                 }),
                 operator: Token {
                     kind: TokenKind::BangEqual,
@@ -801,20 +988,26 @@ mod tests {
                     line: 0,
                     column: 0,
                     literal: None,
+                    span: Span::default(),
+                    doc: None,
+                    leading_trivia: Vec::new(),
+                    trailing_trivia: Vec::new(),
                 },
                 right: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Nil),
+                    span: Span::default(),
                 }),
             };
 
             assert_eq!(
-                evaluate(&expr, &mut Env::new()).unwrap(),
-                Literal::Boolean(true)
+                Interpreter::new().evaluate(&expr, &Environment::new_global()).unwrap(),
+                Value::Boolean(true)
             );
 
             let expr = Expr::BinaryExpression {
                 left: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(10.into())),
+                    span: Span::default(),
                 }),
                 operator: Token {
                     kind: TokenKind::EqualEqual,
@@ -822,15 +1015,20 @@ mod tests {
                     line: 0,
                     column: 0,
                     literal: None,
+                    span: Span::default(),
+                    doc: None,
+                    leading_trivia: Vec::new(),
+                    trailing_trivia: Vec::new(),
                 },
                 right: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Nil),
+                    span: Span::default(),
                 }),
             };
 
             assert_eq!(
-                evaluate(&expr, &mut Env::new()).unwrap(),
-                Literal::Boolean(false)
+                Interpreter::new().evaluate(&expr, &Environment::new_global()).unwrap(),
+                Value::Boolean(false)
             );
         }
 
@@ -843,15 +1041,20 @@ mod tests {
                     line: 0,
                     column: 0,
                     literal: None,
+                    span: Span::default(),
+                    doc: None,
+                    leading_trivia: Vec::new(),
+                    trailing_trivia: Vec::new(),
                 },
                 right: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(1.into())),
+                    span: Span::default(),
                 }),
             };
 
             assert_eq!(
-                evaluate(&expr, &mut Env::new()).unwrap(),
-                Literal::Number((-1).into())
+                Interpreter::new().evaluate(&expr, &Environment::new_global()).unwrap(),
+                Value::Number((-1).into())
             );
 
             let expr = Expr::UnaryExpression {
@@ -861,15 +1064,20 @@ mod tests {
                     line: 0,
                     column: 0,
                     literal: None,
+                    span: Span::default(),
+                    doc: None,
+                    leading_trivia: Vec::new(),
+                    trailing_trivia: Vec::new(),
                 },
                 right: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Boolean(true)),
+                    span: Span::default(),
                 }),
             };
 
             assert_eq!(
-                evaluate(&expr, &mut Env::new()).unwrap(),
-                Literal::Boolean(false)
+                Interpreter::new().evaluate(&expr, &Environment::new_global()).unwrap(),
+                Value::Boolean(false)
             );
         }
     }
@@ -886,20 +1094,25 @@ mod tests {
                     line: 0,
                     column: 0,
                     literal: None,
+                    span: Span::default(),
+                    doc: None,
+                    leading_trivia: Vec::new(),
+                    trailing_trivia: Vec::new(),
                 },
-                initializer: Expr::LiteralExpression {
+                initializer: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(1.into())),
-                },
+                    span: Span::default(),
+                }),
+                doc: None,
             };
 
-            let mut env = Env::new();
-            env.push(HashMap::new());
+            let env = Environment::new_global();
 
-            execute(Box::new(&stmt), &mut env).unwrap();
+            Interpreter::new().execute(&stmt, &env).unwrap();
 
             assert_eq!(
-                get_symbol_in_scope(&env, &"a".into()).unwrap(),
-                &Literal::Number(1.into())
+                env.borrow().get("a").unwrap(),
+                Value::Number(1.into())
             );
         }
     }
@@ -909,59 +1122,66 @@ mod tests {
         let stmt = Stmt::IfStmt {
             condition: Box::new(Expr::LiteralExpression {
                 value: Some(Literal::Boolean(true)),
+                span: Span::default(),
             }),
             then_branch: Box::new(Stmt::ExpressionStmt {
                 expression: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(1.into())),
+                    span: Span::default(),
                 }),
             }),
             else_if_branches: vec![],
             else_branch: None,
         };
 
-        let mut env = Env::new();
-        env.push(HashMap::new());
+        let env = Environment::new_global();
 
-        assert!(execute(Box::new(&stmt), &mut env).is_ok());
+        assert!(Interpreter::new().execute(&stmt, &env).is_ok());
 
         let stmt = Stmt::IfStmt {
             condition: Box::new(Expr::LiteralExpression {
                 value: Some(Literal::Boolean(false)),
+                span: Span::default(),
             }),
             then_branch: Box::new(Stmt::ExpressionStmt {
                 expression: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(1.into())),
+                    span: Span::default(),
                 }),
             }),
             else_if_branches: vec![],
             else_branch: Some(Box::new(Stmt::ExpressionStmt {
                 expression: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(2.into())),
+                    span: Span::default(),
                 }),
             })),
         };
 
-        let mut env = Env::new();
-        env.push(HashMap::new());
+        let env = Environment::new_global();
 
-        assert!(execute(Box::new(&stmt), &mut env).is_ok());
+        assert!(Interpreter::new().execute(&stmt, &env).is_ok());
 
         let stmt = Stmt::IfStmt {
             condition: Box::new(Expr::LiteralExpression {
                 value: Some(Literal::Boolean(false)),
+                span: Span::default(),
             }),
             then_branch: Box::new(Stmt::ExpressionStmt {
                 expression: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(1.into())),
+                    span: Span::default(),
                 }),
             }),
             else_if_branches: vec![Box::new(Stmt::IfStmt {
                 condition: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Boolean(true)),
+                    span: Span::default(),
                 }),
                 then_branch: Box::new(Stmt::ExpressionStmt {
                     expression: Box::new(Expr::LiteralExpression {
                         value: Some(Literal::Number(2.into())),
+                        span: Span::default(),
                     }),
                 }),
                 else_if_branches: vec![],
@@ -970,13 +1190,899 @@ mod tests {
             else_branch: Some(Box::new(Stmt::ExpressionStmt {
                 expression: Box::new(Expr::LiteralExpression {
                     value: Some(Literal::Number(3.into())),
+                    span: Span::default(),
                 }),
             })),
         };
 
-        let mut env = Env::new();
-        env.push(HashMap::new());
+        let env = Environment::new_global();
+
+        assert!(Interpreter::new().execute(&stmt, &env).is_ok());
+    }
+
+    mod functions_tests {
+        use super::*;
+
+        #[test]
+        fn call_returns_value() {
+            let env = run("\
+            fun add(a, b) {
+                return a + b;
+            }
+
+            var result = add(1, 2);
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("result").unwrap(), Value::Number(3.into()));
+        }
+
+        #[test]
+        fn return_unwinds_nested_blocks() {
+            let env = run("\
+            fun first_positive(a, b) {
+                if a > 0 {
+                    {
+                        return a;
+                    }
+                }
+
+                return b;
+            }
+
+            var result = first_positive(1, 2);
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("result").unwrap(), Value::Number(1.into()));
+        }
+
+        #[test]
+        fn recursion() {
+            let env = run("\
+            fun fib(n) {
+                if n < 2 {
+                    return n;
+                }
+
+                return fib(n - 1) + fib(n - 2);
+            }
 
-        assert!(execute(Box::new(&stmt), &mut env).is_ok());
+            var result = fib(10);
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("result").unwrap(), Value::Number(55.into()));
+        }
+
+        #[test]
+        fn closures_capture_their_environment() {
+            let env = run("\
+            fun make_adder(n) {
+                fun add(x) {
+                    return x + n;
+                }
+
+                return add;
+            }
+
+            var add_two = make_adder(2);
+            var result = add_two(3);
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("result").unwrap(), Value::Number(5.into()));
+        }
+
+        #[test]
+        fn function_without_return_returns_nil() {
+            let env = run("\
+            fun noop() {}
+
+            var result = noop();
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("result").unwrap(), Value::Nil);
+        }
+
+        #[test]
+        fn arity_mismatch() {
+            let err = run("\
+            fun add(a, b) {
+                return a + b;
+            }
+
+            add(1);
+            ").unwrap_err();
+
+            assert_eq!(err.msg, "Expected 2 arguments but got 1.");
+        }
+
+        #[test]
+        fn calling_a_non_callable() {
+            let err = run("\
+            var a = 1;
+            a();
+            ").unwrap_err();
+
+            assert_eq!(err.msg, "Can only call functions and classes.");
+        }
+    }
+
+    mod classes_tests {
+        use super::*;
+
+        #[test]
+        fn initializer_and_fields() {
+            let env = run("\
+            class Point {
+                init(x, y) {
+                    self.x = x;
+                    self.y = y;
+                }
+
+                sum() {
+                    return self.x + self.y;
+                }
+            }
+
+            var point = Point(1, 2);
+            point.y = 5;
+            var result = point.sum();
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("result").unwrap(), Value::Number(6.into()));
+        }
+
+        #[test]
+        fn initializer_returns_the_instance() {
+            let env = run("\
+            class Empty {
+                init() {
+                    return;
+                }
+            }
+
+            var empty = Empty();
+            var result = empty.init();
+            ").unwrap();
+
+            let empty = env.borrow().get("empty").unwrap();
+            assert!(matches!(empty, Value::Instance(_)));
+            assert_eq!(env.borrow().get("result").unwrap(), empty);
+        }
+
+        #[test]
+        fn bound_methods_keep_their_instance() {
+            let env = run("\
+            class Counter {
+                init() {
+                    self.count = 0;
+                }
+
+                increment() {
+                    self.count = self.count + 1;
+                    return self.count;
+                }
+            }
+
+            var counter = Counter();
+            var increment = counter.increment;
+            increment();
+            var result = increment();
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("result").unwrap(), Value::Number(2.into()));
+        }
+
+        #[test]
+        fn inheritance_and_super() {
+            let env = run("\
+            class A {
+                name() {
+                    return \"A\";
+                }
+
+                greet() {
+                    return \"Hello from \" + self.name();
+                }
+            }
+
+            class B < A {
+                name() {
+                    return \"B and \" + super.name();
+                }
+            }
+
+            var result = B().greet();
+            ").unwrap();
+
+            assert_eq!(
+                env.borrow().get("result").unwrap(),
+                Value::String("Hello from B and A".into())
+            );
+        }
+
+        #[test]
+        fn undefined_property() {
+            let err = run("\
+            class A {}
+            A().missing;
+            ").unwrap_err();
+
+            assert_eq!(err.msg, "Undefined property \"missing\".");
+        }
+
+        #[test]
+        fn superclass_must_be_a_class() {
+            let err = run("\
+            var NotAClass = 1;
+            class A < NotAClass {}
+            ").unwrap_err();
+
+            assert_eq!(err.msg, "Superclass must be a class.");
+        }
+    }
+
+    mod logical_operators_tests {
+        use super::*;
+
+        #[test]
+        fn result_is_the_deciding_operand() {
+            let env = run("\
+            var a = nil or \"default\";
+            var b = 0 or false;
+            var c = \"first\" or \"second\";
+            var d = 1 and 2;
+            var e = nil and 2;
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("a").unwrap(), Value::String("default".into()));
+            assert_eq!(env.borrow().get("b").unwrap(), Value::Boolean(false));
+            assert_eq!(env.borrow().get("c").unwrap(), Value::String("first".into()));
+            assert_eq!(env.borrow().get("d").unwrap(), Value::Number(2.into()));
+            assert_eq!(env.borrow().get("e").unwrap(), Value::Nil);
+        }
+
+        #[test]
+        fn right_operand_is_skipped_once_the_left_one_decides() {
+            let env = run("\
+            var calls = 0;
+            fun expensive() {
+                calls = calls + 1;
+                return true;
+            }
+
+            var a = true or expensive();
+            var b = false and expensive();
+            var c = false or expensive();
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("calls").unwrap(), Value::Number(1.into()));
+        }
+
+        #[test]
+        fn guards_a_property_access() {
+            let env = run("\
+            class Box {}
+            var empty = nil;
+            var result = empty != nil and empty.field;
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("result").unwrap(), Value::Boolean(false));
+        }
+    }
+
+    mod interpolation_tests {
+        use super::*;
+
+        #[test]
+        fn stringifies_each_expression() {
+            let source = "\
+            var name = \"Ada\";
+            var count = 2;
+            var a = \"Hello, ${name}! You have ${count + 1} items\";
+            var b = \"${nil} ${true} ${1.5} ${\"x\" + \"${count}\"}\";
+            ";
+
+            let env = run(source).unwrap();
+            assert_eq!(
+                env.borrow().get("a").unwrap(),
+                Value::String("Hello, Ada! You have 3 items".into())
+            );
+            assert_eq!(env.borrow().get("b").unwrap(), Value::String("nil true 1.5 x2".into()));
+        }
+
+        #[test]
+        fn evaluates_in_order() {
+            let source = "\
+            var calls = \"\";
+            fun f(name) {
+                calls = calls + name;
+                return name;
+            }
+            var a = \"${f(\"1\")}${f(\"2\")}\";
+            ";
+
+            let env = run(source).unwrap();
+            assert_eq!(env.borrow().get("calls").unwrap(), Value::String("12".into()));
+            assert_eq!(env.borrow().get("a").unwrap(), Value::String("12".into()));
+        }
+
+        #[test]
+        fn numbers_print_per_dialect() {
+            let env = run_with_dialect("var a = \"${1 / 0}\";", Dialect::Reference).unwrap();
+
+            assert_eq!(env.borrow().get("a").unwrap(), Value::String("Infinity".into()));
+        }
+    }
+
+    mod conditional_tests {
+        use super::*;
+
+        #[test]
+        fn picks_a_branch() {
+            let source = "\
+            var count = 1;
+            var label = count == 1 ? \"item\" : \"items\";
+            count = 3;
+            var plural = count == 1 ? \"item\" : \"items\";
+            var sign = count < 0 ? -1 : count == 0 ? 0 : 1;
+            ";
+
+            let env = run(source).unwrap();
+            assert_eq!(env.borrow().get("label").unwrap(), Value::String("item".into()));
+            assert_eq!(env.borrow().get("plural").unwrap(), Value::String("items".into()));
+            assert_eq!(env.borrow().get("sign").unwrap(), Value::Number(1.into()));
+        }
+
+        #[test]
+        fn only_evaluates_the_picked_branch() {
+            let source = "\
+            var calls = \"\";
+            fun f(name) {
+                calls += name;
+                return name;
+            }
+            var a = true ? f(\"then\") : f(\"else\");
+            var b = nil ? f(\"then\") : f(\"else\");
+            ";
+
+            let env = run(source).unwrap();
+            assert_eq!(env.borrow().get("calls").unwrap(), Value::String("thenelse".into()));
+        }
+
+        #[test]
+        fn condition_follows_the_dialect() {
+            let source = "var a = 0 ? \"truthy\" : \"falsey\";";
+
+            let env = run_with_dialect(source, Dialect::Extended).unwrap();
+            assert_eq!(env.borrow().get("a").unwrap(), Value::String("falsey".into()));
+
+            let env = run_with_dialect(source, Dialect::Reference).unwrap();
+            assert_eq!(env.borrow().get("a").unwrap(), Value::String("truthy".into()));
+        }
+    }
+
+    mod native_function_tests {
+        use super::*;
+
+        #[test]
+        fn are_called_like_functions() {
+            let source = "\
+            var time = clock();
+            var printed = \"${clock}\";
+            ";
+
+            let env = run(source).unwrap();
+            assert!(matches!(env.borrow().get("time").unwrap(), Value::Number(time) if time > 0f64));
+            assert_eq!(env.borrow().get("printed").unwrap(), Value::String("<native fn clock>".into()));
+        }
+
+        #[test]
+        fn list_builds_a_shared_list() {
+            let source = "\
+            var a = list(3, nil);
+            var b = a;
+            var printed = \"${a}\";
+            var same = a == b;
+            var different = list(0, 1) == list(0, 1);
+            var empty = list(0, 1) or \"falsey\";
+            ";
+
+            let env = run(source).unwrap();
+            assert_eq!(env.borrow().get("printed").unwrap(), Value::String("[nil, nil, nil]".into()));
+            assert_eq!(env.borrow().get("same").unwrap(), Value::Boolean(true));
+            assert_eq!(env.borrow().get("different").unwrap(), Value::Boolean(false));
+            assert_eq!(env.borrow().get("empty").unwrap(), Value::String("falsey".into()));
+        }
+
+        #[test]
+        fn report_errors_at_the_call() {
+            let err = run("clock(1);").unwrap_err();
+            assert_eq!(err.msg, "Expected 0 arguments but got 1.");
+            assert_eq!(err.column, 8);
+
+            let err = run("list(1.5, nil);").unwrap_err();
+            assert_eq!(err.msg, "\"list\" expects a whole number of at least 0 as its length.");
+            assert_eq!(err.column, 14);
+        }
+    }
+
+    mod dialect_tests {
+        use super::*;
+
+        #[test]
+        fn truthiness() {
+            let source = "\
+            var zero = 0 or \"falsey\";
+            var empty = \"\" or \"falsey\";
+            ";
+
+            let env = run_with_dialect(source, Dialect::Extended).unwrap();
+            assert_eq!(env.borrow().get("zero").unwrap(), Value::String("falsey".into()));
+            assert_eq!(env.borrow().get("empty").unwrap(), Value::String("falsey".into()));
+
+            let env = run_with_dialect(source, Dialect::Reference).unwrap();
+            assert_eq!(env.borrow().get("zero").unwrap(), Value::Number(0.into()));
+            assert_eq!(env.borrow().get("empty").unwrap(), Value::String("".into()));
+        }
+
+        #[test]
+        fn adding_a_number_to_a_string() {
+            let env = run_with_dialect("var a = \"n\" + 1;", Dialect::Extended).unwrap();
+            assert_eq!(env.borrow().get("a").unwrap(), Value::String("n1".into()));
+
+            let err = run_with_dialect("var a = \"n\" + 1;", Dialect::Reference).unwrap_err();
+            assert_eq!(err.msg, "Operands of \"+\" must be two numbers or two strings.");
+        }
+
+        #[test]
+        fn equality() {
+            let source = "\
+            var bool_is_nil = false == nil;
+            var bool_is_not_nil = false != nil;
+            var nan = 0 / 0;
+            var nan_is_nan = nan == nan;
+            ";
+
+            let env = run_with_dialect(source, Dialect::Reference).unwrap();
+            assert_eq!(env.borrow().get("bool_is_nil").unwrap(), Value::Boolean(false));
+            assert_eq!(env.borrow().get("bool_is_not_nil").unwrap(), Value::Boolean(true));
+            assert_eq!(env.borrow().get("nan_is_nan").unwrap(), Value::Boolean(false));
+        }
+
+        #[test]
+        fn dividing_by_zero() {
+            let err = run_with_dialect("var a = 1 / 0;", Dialect::Extended).unwrap_err();
+            assert_eq!(err.msg, "Cannot divide by zero.");
+
+            let env = run_with_dialect("var a = 1 / 0;", Dialect::Reference).unwrap();
+            assert_eq!(env.borrow().get("a").unwrap(), Value::Number(f64::INFINITY));
+        }
+
+        #[test]
+        fn printing_numbers() {
+            assert_eq!(stringify(&Value::Number(-0.0), Dialect::Reference), "-0");
+            assert_eq!(stringify(&Value::Number(f64::NAN), Dialect::Reference), "NaN");
+            assert_eq!(stringify(&Value::Number(f64::NEG_INFINITY), Dialect::Reference), "-Infinity");
+            assert_eq!(stringify(&Value::Number(2.5), Dialect::Reference), "2.5");
+            assert_eq!(stringify(&Value::Number(3.0), Dialect::Reference), "3");
+        }
+
+        #[test]
+        fn shadowing() {
+            let source = "\
+            var a = 1;
+            var a = 2;
+            {
+                var a = 3;
+            }
+            ";
+
+            let err = run_with_dialect(source, Dialect::Extended).unwrap_err();
+            assert_eq!(err.msg, "Variable \"a\" already declared.");
+
+            let env = run_with_dialect(source, Dialect::Reference).unwrap();
+            assert_eq!(env.borrow().get("a").unwrap(), Value::Number(2.into()));
+        }
+    }
+
+    mod loops_tests {
+        use super::*;
+
+        #[test]
+        fn while_loop() {
+            let env = run("\
+            var i = 0;
+            var sum = 0;
+            while i < 5 {
+                i = i + 1;
+                sum = sum + i;
+            }
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("sum").unwrap(), Value::Number(15.into()));
+        }
+
+        #[test]
+        fn condition_only_for_loop() {
+            let env = run("\
+            var a = 1;
+            for a < 10 {
+                a = a * 2;
+            }
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("a").unwrap(), Value::Number(16.into()));
+        }
+
+        #[test]
+        fn c_style_for_loop() {
+            let env = run("\
+            var sum = 0;
+            for var i = 1; i < 4; i = i + 1 {
+                sum = sum + i;
+            }
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("sum").unwrap(), Value::Number(6.into()));
+            // The loop variable is scoped to the loop.
+            assert_eq!(env.borrow().get("i"), None);
+        }
+
+        #[test]
+        fn closures_capture_the_loop_variable_of_their_iteration() {
+            let env = run("\
+            var getters = nil;
+            for var i = 0; i < 3; i = i + 1 {
+                var previous = getters;
+                fun get(index) {
+                    if index == i {
+                        return i;
+                    }
+                    return previous(index);
+                }
+                getters = get;
+            }
+            var first = getters(0);
+            var second = getters(1);
+            var third = getters(2);
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("first").unwrap(), Value::Number(0.into()));
+            assert_eq!(env.borrow().get("second").unwrap(), Value::Number(1.into()));
+            assert_eq!(env.borrow().get("third").unwrap(), Value::Number(2.into()));
+        }
+
+        #[test]
+        fn every_iteration_gets_a_fresh_scope() {
+            let env = run("\
+            var last = nil;
+            for var i = 0; i < 3; i = i + 1 {
+                var doubled = i * 2;
+                last = doubled;
+            }
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("last").unwrap(), Value::Number(4.into()));
+        }
+
+        #[test]
+        fn return_from_inside_a_loop() {
+            let env = run("\
+            fun first_over(limit) {
+                for var i = 0; ; i = i + 1 {
+                    if i * i > limit {
+                        return i;
+                    }
+                }
+            }
+
+            var result = first_over(50);
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("result").unwrap(), Value::Number(8.into()));
+        }
+
+        #[test]
+        fn break_exits_the_innermost_loop() {
+            let env = run("\
+            var count = 0;
+            for var i = 0; i < 3; i = i + 1 {
+                while true {
+                    if count > 100 {
+                        break;
+                    }
+                    break;
+                }
+                count = count + 1;
+            }
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("count").unwrap(), Value::Number(3.into()));
+        }
+
+        #[test]
+        fn continue_still_runs_the_increment() {
+            let env = run("\
+            var sum = 0;
+            for var i = 0; i < 6; i = i + 1 {
+                if i == 3 {
+                    continue;
+                }
+                sum = sum + i;
+            }
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("sum").unwrap(), Value::Number(12.into()));
+        }
+
+        #[test]
+        fn labeled_break_and_continue() {
+            let env = run("\
+            var pairs = 0;
+            var found = nil;
+            outer: for var i = 1; i < 10; i = i + 1 {
+                inner: for var j = 1; j < 10; j = j + 1 {
+                    if j > i {
+                        continue outer;
+                    }
+                    pairs = pairs + 1;
+                    if i * j == 12 {
+                        found = i;
+                        break outer;
+                    }
+                }
+            }
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("found").unwrap(), Value::Number(4.into()));
+            assert_eq!(env.borrow().get("pairs").unwrap(), Value::Number(9.into()));
+        }
+    }
+
+    mod interpreter_tests {
+        use super::*;
+
+        fn parse(source: &str) -> Vec<Stmt> {
+            let mut tokenizer = Tokenizer::new(source);
+            let (tokens, _) = tokenizer.scan_tokens();
+
+            let (mut statements, _) = Parser::new(tokens).parse();
+            Resolver::new().resolve(&mut statements);
+
+            return statements;
+        }
+
+        #[test]
+        fn state_persists_between_runs() {
+            let mut interpreter = Interpreter::new();
+
+            interpreter.interpret(&parse("var a = 1;")).unwrap();
+            interpreter.interpret(&parse("fun double(x) { return x * 2; }")).unwrap();
+            interpreter.interpret(&parse("var b = double(a + 1);")).unwrap();
+
+            assert_eq!(
+                interpreter.globals.borrow().get("b").unwrap(),
+                Value::Number(4.into())
+            );
+        }
+
+        #[test]
+        fn runtime_error_keeps_earlier_definitions() {
+            let mut interpreter = Interpreter::new();
+
+            let err = interpreter
+                .interpret(&parse("var a = 1; var b = a / 0; var c = 3;"))
+                .unwrap_err();
+            assert_eq!(err.msg, "Cannot divide by zero.");
+
+            interpreter.interpret(&parse("var d = a + 1;")).unwrap();
+
+            assert_eq!(interpreter.globals.borrow().get("c"), None);
+            assert_eq!(
+                interpreter.globals.borrow().get("d").unwrap(),
+                Value::Number(2.into())
+            );
+        }
+
+        #[test]
+        fn runtime_errors_point_at_the_offending_token() {
+            let source = "var a = 1;\nvar b = a + nil;";
+            let err = run(source).unwrap_err();
+
+            assert_eq!(err.line, Some(2));
+            assert_eq!(err.column, 11);
+
+            let span = err.span.unwrap();
+            assert_eq!(&source[span.start..span.end], "+");
+        }
+
+        #[test]
+        fn remainder_integer_division_and_exponent() {
+            let source = "\
+            var a = 7 % 3;
+            var b = 7 ~/ 2;
+            var c = -2 ** 2;
+            var d = 2 ** 3 ** 2;
+            ";
+
+            let env = run(source).unwrap();
+            assert_eq!(env.borrow().get("a").unwrap(), Value::Number(1.into()));
+            assert_eq!(env.borrow().get("b").unwrap(), Value::Number(3.into()));
+            assert_eq!(env.borrow().get("c").unwrap(), Value::Number((-4).into()));
+            assert_eq!(env.borrow().get("d").unwrap(), Value::Number(512.into()));
+        }
+
+        #[test]
+        fn division_by_zero_points_at_the_operator() {
+            for (source, operator) in [("var a = 1 / 0;", "/"), ("var a = 1 % 0;", "%"), ("var a = 1 ~/ 0;", "~/")] {
+                let err = run(source).unwrap_err();
+                assert_eq!(err.msg, "Cannot divide by zero.");
+
+                let span = err.span.unwrap();
+                assert_eq!(&source[span.start..span.end], operator);
+            }
+        }
+
+        #[test]
+        fn not_equal_is_the_opposite_of_equal() {
+            let source = "\
+            var a = nil != false;
+            var b = 1 != \"1\";
+            var c = nil == false;
+            ";
+
+            for dialect in [Dialect::Extended, Dialect::Reference] {
+                let env = run_with_dialect(source, dialect).unwrap();
+                assert_eq!(env.borrow().get("a").unwrap(), Value::Boolean(true));
+                assert_eq!(env.borrow().get("b").unwrap(), Value::Boolean(true));
+                assert_eq!(env.borrow().get("c").unwrap(), Value::Boolean(false));
+            }
+        }
+    }
+
+    mod assignment_tests {
+        use super::*;
+
+        #[test]
+        fn assignment_updates_the_declaring_scope() {
+            let env = run("\
+            var a = 1;
+            {
+                {
+                    a = 2;
+                }
+            }
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("a").unwrap(), Value::Number(2.into()));
+        }
+
+        #[test]
+        fn chained_assignment() {
+            let env = run("\
+            var a = 1;
+            var b = 2;
+            a = b = 5;
+            var c = (a = 7) + b;
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("a").unwrap(), Value::Number(7.into()));
+            assert_eq!(env.borrow().get("b").unwrap(), Value::Number(5.into()));
+            assert_eq!(env.borrow().get("c").unwrap(), Value::Number(12.into()));
+        }
+
+        #[test]
+        fn compound_assignment_to_variables() {
+            let env = run("\
+            var a = 10;
+            var b = \"x\";
+            var c;
+            {
+                var local = 1;
+                local += 2;
+                a -= local;
+                c = local *= 4;
+            }
+            a %= 4;
+            b += 1;
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("a").unwrap(), Value::Number(3.into()));
+            assert_eq!(env.borrow().get("b").unwrap(), Value::String("x1".into()));
+            assert_eq!(env.borrow().get("c").unwrap(), Value::Number(12.into()));
+        }
+
+        #[test]
+        fn compound_assignment_evaluates_the_target_once() {
+            let env = run("\
+            class Box {}
+            var calls = 0;
+            var box = Box();
+            box.count = 1;
+            fun get_box() {
+                calls += 1;
+                return box;
+            }
+            get_box().count += 5;
+            var count = box.count;
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("calls").unwrap(), Value::Number(1.into()));
+            assert_eq!(env.borrow().get("count").unwrap(), Value::Number(6.into()));
+        }
+
+        #[test]
+        fn compound_assignment_errors() {
+            let err = |source: &str| run(source).unwrap_err().msg;
+
+            assert_eq!(err("a += 1;"), "Usage of undeclared variable \"a\".");
+            assert_eq!(err("var a = nil; a -= 1;"), "Operands of \"-\" must be two numbers.");
+            assert_eq!(err("var a = 1; a /= 0;"), "Cannot divide by zero.");
+            assert_eq!(err("class A {} var a = A(); a.b += 1;"), "Undefined property \"b\".");
+            assert_eq!(err("var a = 1; a.b += 1;"), "Only instances have fields.");
+            assert_eq!(err("var a = 1; a + 1 = 2;"), "Invalid assignment target.");
+        }
+
+        #[test]
+        fn increment_and_decrement() {
+            let env = run("\
+            class Box {}
+            var calls = 0;
+            var box = Box();
+            box.count = 1;
+            fun get_box() {
+                calls += 1;
+                return box;
+            }
+            var a = 1;
+            var before = a++;
+            var after = ++a;
+            var old_count = get_box().count--;
+            --box.count;
+            var count = box.count;
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("before").unwrap(), Value::Number(1.into()));
+            assert_eq!(env.borrow().get("after").unwrap(), Value::Number(3.into()));
+            assert_eq!(env.borrow().get("a").unwrap(), Value::Number(3.into()));
+            assert_eq!(env.borrow().get("calls").unwrap(), Value::Number(1.into()));
+            assert_eq!(env.borrow().get("old_count").unwrap(), Value::Number(1.into()));
+            assert_eq!(env.borrow().get("count").unwrap(), Value::Number((-1).into()));
+
+            let err = run("var s = \"a\";\ns++;").unwrap_err();
+            assert_eq!(err.msg, "Operand of \"++\" must be a number.");
+            assert_eq!((err.line, err.column), (Some(2), 2));
+        }
+
+        #[test]
+        fn closures_share_the_captured_variable() {
+            let env = run("\
+            fun make_counter() {
+                var count = 0;
+                fun increment() {
+                    count = count + 1;
+                    return count;
+                }
+                return increment;
+            }
+
+            var counter = make_counter();
+            counter();
+            counter();
+            var result = counter();
+            ").unwrap();
+
+            assert_eq!(env.borrow().get("result").unwrap(), Value::Number(3.into()));
+        }
+
+        #[test]
+        fn assignment_of_undeclared_variable() {
+            let err = run("\
+            {
+                missing = 1;
+            }
+            ").unwrap_err();
+
+            assert_eq!(err.msg, "Assignment of undeclared variable \"missing\".");
+        }
     }
 }
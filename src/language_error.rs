@@ -1,18 +1,151 @@
+use crate::span::Span;
+
 #[derive(Debug)]
 pub struct Error {
     pub msg: String,
     pub line: Option<usize>,
     pub column: usize,
+    /// The part of the source code the error is about, if it's known.
+    pub span: Option<Span>,
     pub hint: Option<String>,
 }
 
 impl Error {
-    pub fn new(msg: String, line: Option<usize>, column: usize, hint: Option<String>) -> Error {
+    pub fn new(
+        msg: String,
+        line: Option<usize>,
+        column: usize,
+        span: Option<Span>,
+        hint: Option<String>,
+    ) -> Error {
         return Error {
             line,
             column,
+            span,
             msg,
             hint,
         };
     }
 }
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const CYAN: &str = "\x1b[1;36m";
+const RESET: &str = "\x1b[0m";
+
+impl Error {
+    /// Renders the error for the user: the message, where it is, the line of `source` it's about
+    /// with the offending part underlined, and the hint. `color` adds ANSI colors to it.
+    pub fn render(&self, source: &str, color: bool) -> String {
+        let paint = |text: &str, style: &str| -> String {
+            return if color {
+                format!("{}{}{}", style, text, RESET)
+            } else {
+                text.into()
+            };
+        };
+
+        let mut ret = format!("{} {}\n", paint("error:", RED), self.msg);
+
+        if let Some(line) = self.line {
+            let gutter = " ".repeat(line.to_string().len());
+            ret += format!(
+                "{}{} line {}, column {}\n",
+                gutter,
+                paint("-->", BLUE),
+                line,
+                self.column
+            )
+            .as_str();
+
+            if let Some((text, underline)) = self.span.and_then(|span| snippet(source, span)) {
+                ret += format!("{} {}\n", gutter, paint("|", BLUE)).as_str();
+                ret += format!("{} {}\n", paint(&format!("{} |", line), BLUE), text).as_str();
+                ret += format!("{} {} {}\n", gutter, paint("|", BLUE), paint(&underline, RED))
+                    .as_str();
+            }
+        }
+
+        if let Some(hint) = &self.hint {
+            ret += format!("{} {}\n", paint("hint:", CYAN), hint).as_str();
+        }
+
+        return ret.trim_end().into();
+    }
+}
+
+/// Returns the line of `source` the span starts on, and the underline that goes below it. Returns
+/// `None` if the span isn't in `source`.
+fn snippet(source: &str, span: Span) -> Option<(String, String)> {
+    if span.start > span.end
+        || span.end > source.len()
+        || !source.is_char_boundary(span.start)
+        || !source.is_char_boundary(span.end)
+    {
+        return None;
+    }
+
+    let line_start = source[..span.start].rfind('\n').map_or(0, |index| index + 1);
+    let line_end = source[span.start..]
+        .find('\n')
+        .map_or(source.len(), |index| span.start + index);
+    let line = source[line_start..line_end].trim_end_matches('\r');
+
+    // Tabs are kept in the padding so the underline lines up with the text above it.
+    let padding: String = source[line_start..span.start]
+        .chars()
+        .map(|char| if char == '\t' { '\t' } else { ' ' })
+        .collect();
+    let width = source[span.start..span.end.min(line_end)].chars().count().max(1);
+
+    return Some((line.into(), padding + "^".repeat(width).as_str()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn underlines_the_span() {
+        let source = "var a = 1;\nvar b = a + \"x\";\n";
+        let err = Error::new(
+            "Operands of \"+\" must be two numbers or two strings.".into(),
+            Some(2),
+            11,
+            Some(Span::new(21, 22)),
+            Some("Convert the number with a function first.".into()),
+        );
+
+        assert_eq!(
+            err.render(source, false),
+            "\
+error: Operands of \"+\" must be two numbers or two strings.
+ --> line 2, column 11
+  |
+2 | var b = a + \"x\";
+  |           ^
+hint: Convert the number with a function first."
+        );
+    }
+
+    #[test]
+    fn underlines_every_character_of_the_span() {
+        let source = "print missing;";
+        let err = Error::new(
+            "Usage of undeclared variable \"missing\".".into(),
+            Some(1),
+            7,
+            Some(Span::new(6, 13)),
+            None,
+        );
+
+        assert!(err.render(source, false).ends_with("1 | print missing;\n  |       ^^^^^^^"));
+    }
+
+    #[test]
+    fn skips_the_snippet_without_a_span() {
+        let err = Error::new("Error writing to stdout".into(), None, 0, None, None);
+
+        assert_eq!(err.render("", false), "error: Error writing to stdout");
+    }
+}
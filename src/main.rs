@@ -1,36 +1,70 @@
-use std::io::Write;
+// The codebase spells out `return`, names AST variants after their kind (`...Expression`,
+// `...Stmt`) and boxes AST children on purpose.
+#![allow(
+    clippy::needless_return,
+    clippy::enum_variant_names,
+    clippy::vec_box,
+    clippy::inherent_to_string_shadow_display
+)]
+
+use std::io::{IsTerminal, Write};
 use std::{fs, io};
 
 use tokenizer::Tokenizer;
 
+use crate::dialect::Dialect;
+use crate::interpreter::Interpreter;
 use crate::language_error::Error;
 use crate::parser::Parser;
+use crate::resolver::Resolver;
+use crate::trivia::Trivia;
 
 mod ast_printer;
+mod class;
+mod dialect;
+mod environment;
 mod expressions;
+mod function;
 mod interpreter;
 mod language_error;
 mod literal;
+mod operators;
 mod parser;
+mod resolver;
+mod span;
 mod stmt;
 mod token;
 mod token_kinds;
 mod tokenizer;
+mod trivia;
+mod value;
 
 fn main() {
-    let args = std::env::args().collect::<Vec<String>>();
-
-    match args.len() {
-        2 => {
-            run_file(args.get(1).unwrap_or_else(|| {
-                println!("Error reading source file");
+    let mut dialect = Dialect::default();
+    let mut show_tokens = false;
+    let mut show_ast = false;
+    let mut files = Vec::<String>::new();
+
+    for arg in std::env::args().skip(1) {
+        if let Some(name) = arg.strip_prefix("--dialect=") {
+            dialect = Dialect::from_name(name).unwrap_or_else(|| {
+                println!("Unknown dialect \"{}\". Expected \"extended\" or \"reference\".", name);
                 std::process::exit(1);
-            }));
-        }
-        1 => {
-            run_prompt();
+            });
+        } else if arg == "--tokens" {
+            show_tokens = true;
+        } else if arg == "--ast" {
+            show_ast = true;
+        } else {
+            files.push(arg);
         }
-        _ => println!("Usage: lox <filename>"),
+    }
+
+    match files.len() {
+        1 if show_tokens => print_tokens(&files[0]),
+        1 => run_file(&files[0], dialect, show_ast),
+        0 if !show_tokens => run_prompt(dialect, show_ast),
+        _ => println!("Usage: lox [--dialect=extended|reference] [--tokens] [--ast] [filename]"),
     }
 }
 
@@ -39,18 +73,66 @@ enum RunMode {
     Prompt,
 }
 
-/// Run a source file.
-pub fn run_file(file_name: &String) {
-    let content = fs::read_to_string(file_name).unwrap_or_else(|err| {
+/// The exit code for a file with a syntax or resolution error, the same as the reference
+/// implementation's.
+const EXIT_COMPILE_ERROR: i32 = 65;
+/// The exit code for a file that fails while running, the same as the reference implementation's.
+const EXIT_RUNTIME_ERROR: i32 = 70;
+
+/// Run a source file. `show_ast` prints its AST before running it.
+pub fn run_file(file_name: &String, dialect: Dialect, show_ast: bool) {
+    let content = read_source_file(file_name);
+
+    run(content.as_str(), 0, RunMode::File, show_ast, &mut Interpreter::with_dialect(dialect));
+}
+
+/// Print the tokens of a source file, one per line, with the whitespace and comments around them.
+/// Nothing is run. Printing the leading trivia, lexeme and trailing trivia of every token gives
+/// back the source file.
+pub fn print_tokens(file_name: &String) {
+    let content = read_source_file(file_name);
+
+    let mut tokenizer = Tokenizer::with_trivia(content.as_str());
+    let (tokens, tokenizer_errors) = tokenizer.scan_tokens();
+
+    for err in tokenizer_errors {
+        report_error(err, content.as_str());
+    }
+
+    let describe = |trivia: &Vec<Trivia>| -> String {
+        let pieces: Vec<String> = trivia.iter().map(|piece| piece.to_string()).collect();
+
+        return format!("[{}]", pieces.join(", "));
+    };
+
+    for token in tokens {
+        println!(
+            "{}:{} {:?} {:?} leading: {} trailing: {}",
+            token.line,
+            token.column,
+            token.kind,
+            token.lexeme,
+            describe(&token.leading_trivia),
+            describe(&token.trailing_trivia)
+        );
+    }
+}
+
+fn read_source_file(file_name: &String) -> String {
+    return fs::read_to_string(file_name).unwrap_or_else(|err| {
         println!("Error reading source file: {}", err);
         std::process::exit(1);
     });
-
-    run(content.as_str(), RunMode::File);
 }
 
-/// Run the REPL.
-pub fn run_prompt() {
+/// Run the REPL. Every line runs in the same interpreter, so definitions carry over to the next
+/// lines. `show_ast` prints the AST of every line before running it.
+pub fn run_prompt(dialect: Dialect, show_ast: bool) {
+    let mut interpreter = Interpreter::with_dialect(dialect);
+    // Every line entered so far. Errors can be about code from an earlier line, like the body of a
+    // function it declared, so they're shown against all of it.
+    let mut session = String::new();
+
     loop {
         print!("Lox> ");
         io::stdout().flush().unwrap_or_else(|err| {
@@ -59,53 +141,85 @@ pub fn run_prompt() {
         });
 
         let mut input = String::new();
-        io::stdin().read_line(&mut input).unwrap_or_else(|err| {
+        let read = io::stdin().read_line(&mut input).unwrap_or_else(|err| {
             println!("Error reading stdin: {}", err);
             std::process::exit(1);
         });
 
-        run(input.as_str(), RunMode::Prompt);
+        // End of input (e.g. Ctrl-D) ends the session.
+        if read == 0 {
+            println!();
+            return;
+        }
+
+        let offset = session.len();
+        session += input.as_str();
+
+        run(session.as_str(), offset, RunMode::Prompt, show_ast, &mut interpreter);
     }
 }
 
-fn run(input: &str, run_mode: RunMode) {
-    let mut tokenizer = Tokenizer::new(input);
+/// Run the code in `source` that starts at the byte `offset`. Everything before it already ran.
+fn run(source: &str, offset: usize, run_mode: RunMode, show_ast: bool, interpreter: &mut Interpreter) {
+    let mut tokenizer = Tokenizer::starting_at(source, offset);
     let (tokens, tokenizer_errors) = tokenizer.scan_tokens();
 
-    if tokenizer_errors.len() > 0 {
+    if !tokenizer_errors.is_empty() {
         for err in tokenizer_errors {
-            report_error(err);
+            report_error(err, source);
         }
 
-        std::process::exit(70);
+        match run_mode {
+            RunMode::File => std::process::exit(EXIT_COMPILE_ERROR),
+            // Keep the session (and everything defined in it) alive.
+            RunMode::Prompt => return,
+        }
     }
 
     let mut parser = Parser::new(tokens);
-    let statements = parser.parse();
+    let (mut statements, parser_errors) = parser.parse();
 
-    match run_mode {
-        RunMode::File => {
-            if parser.errors.len() != 0 {
-                std::process::exit(1);
-            }
+    if !parser_errors.is_empty() {
+        for err in &parser_errors {
+            report_error(err, source);
         }
-        RunMode::Prompt => {
-            if parser.errors.len() != 0 {
-                return;
-            }
+
+        match run_mode {
+            RunMode::File => std::process::exit(EXIT_COMPILE_ERROR),
+            RunMode::Prompt => return,
         }
     }
 
-    ast_printer::print_ast(&statements);
+    let resolver_errors = Resolver::with_dialect(interpreter.dialect()).resolve(&mut statements);
 
-    interpreter::interpret(&statements);
-}
+    if !resolver_errors.is_empty() {
+        for err in &resolver_errors {
+            report_error(err, source);
+        }
+
+        match run_mode {
+            RunMode::File => std::process::exit(EXIT_COMPILE_ERROR),
+            RunMode::Prompt => return,
+        }
+    }
 
-/// Report a compiler error.
-pub fn report_error(err: &Error) {
-    if let Some(line) = err.line {
-        eprintln!("Found an error at line {}. {}", line, err.msg);
-    } else {
-        eprintln!("{}", err.msg);
+    if show_ast {
+        ast_printer::print_ast(&statements);
     }
+
+    if let Err(err) = interpreter.interpret(&statements) {
+        report_error(&err, source);
+
+        if let RunMode::File = run_mode {
+            std::process::exit(EXIT_RUNTIME_ERROR);
+        }
+    }
+}
+
+/// Report an error, showing the part of `source` it's about. Colors are used when stderr is a
+/// terminal, unless the `NO_COLOR` environment variable is set.
+pub fn report_error(err: &Error, source: &str) {
+    let color = io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+
+    eprintln!("{}", err.render(source, color));
 }
@@ -0,0 +1,526 @@
+use crate::dialect::Dialect;
+use crate::token_kinds::TokenKind;
+use crate::value::Value;
+
+/// An operator with two operands. `and` and `or` aren't here, since they short-circuit and never
+/// need both operands evaluated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    /// The remainder of a division, with the sign of the dividend: `-7 % 2` is `-1`.
+    Remainder,
+    /// A division rounded towards zero: `-7 ~/ 2` is `-3`.
+    IntegerDivide,
+    Power,
+    Equal,
+    NotEqual,
+    Greater,
+    GreaterEqual,
+    Less,
+    LessEqual,
+}
+
+impl BinaryOperator {
+    /// Returns the operator the given token stands for, if it's a binary operator.
+    pub fn from_token(kind: &TokenKind) -> Option<BinaryOperator> {
+        return match kind {
+            TokenKind::Plus => Some(BinaryOperator::Add),
+            TokenKind::Minus => Some(BinaryOperator::Subtract),
+            TokenKind::Star => Some(BinaryOperator::Multiply),
+            TokenKind::Slash => Some(BinaryOperator::Divide),
+            TokenKind::Percent => Some(BinaryOperator::Remainder),
+            TokenKind::TildeSlash => Some(BinaryOperator::IntegerDivide),
+            TokenKind::StarStar => Some(BinaryOperator::Power),
+            TokenKind::EqualEqual => Some(BinaryOperator::Equal),
+            TokenKind::BangEqual => Some(BinaryOperator::NotEqual),
+            TokenKind::Greater => Some(BinaryOperator::Greater),
+            TokenKind::GreaterEqual => Some(BinaryOperator::GreaterEqual),
+            TokenKind::Less => Some(BinaryOperator::Less),
+            TokenKind::LessEqual => Some(BinaryOperator::LessEqual),
+            _ => None,
+        };
+    }
+
+    /// Returns the operator a compound assignment token applies, such as `+` for `+=` and `++`.
+    pub fn from_compound_assignment(kind: &TokenKind) -> Option<BinaryOperator> {
+        return match kind {
+            TokenKind::PlusEqual | TokenKind::PlusPlus => Some(BinaryOperator::Add),
+            TokenKind::MinusEqual | TokenKind::MinusMinus => Some(BinaryOperator::Subtract),
+            TokenKind::StarEqual => Some(BinaryOperator::Multiply),
+            TokenKind::SlashEqual => Some(BinaryOperator::Divide),
+            TokenKind::PercentEqual => Some(BinaryOperator::Remainder),
+            _ => None,
+        };
+    }
+
+    /// The operator as it's written in source code.
+    pub fn symbol(&self) -> &'static str {
+        return match self {
+            BinaryOperator::Add => "+",
+            BinaryOperator::Subtract => "-",
+            BinaryOperator::Multiply => "*",
+            BinaryOperator::Divide => "/",
+            BinaryOperator::Remainder => "%",
+            BinaryOperator::IntegerDivide => "~/",
+            BinaryOperator::Power => "**",
+            BinaryOperator::Equal => "==",
+            BinaryOperator::NotEqual => "!=",
+            BinaryOperator::Greater => ">",
+            BinaryOperator::GreaterEqual => ">=",
+            BinaryOperator::Less => "<",
+            BinaryOperator::LessEqual => "<=",
+        };
+    }
+}
+
+/// An operator with a single operand, written before it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnaryOperator {
+    Negate,
+    Not,
+}
+
+impl UnaryOperator {
+    /// Returns the operator the given token stands for, if it's a unary operator.
+    pub fn from_token(kind: &TokenKind) -> Option<UnaryOperator> {
+        return match kind {
+            TokenKind::Minus => Some(UnaryOperator::Negate),
+            TokenKind::Bang => Some(UnaryOperator::Not),
+            _ => None,
+        };
+    }
+
+    /// The operator as it's written in source code.
+    pub fn symbol(&self) -> &'static str {
+        return match self {
+            UnaryOperator::Negate => "-",
+            UnaryOperator::Not => "!",
+        };
+    }
+}
+
+/// Applies a binary operator to its operands. Everything that runs Lox code goes through here, so
+/// the operators behave the same everywhere. Errors are only messages, which the caller reports
+/// where the operator is.
+///
+/// * `+` adds two numbers or concatenates two strings. The extended dialect also concatenates a
+///   string with a number, on either side.
+/// * `-`, `*`, `/`, `%`, `~/` and `**` only take numbers. The extended dialect doesn't allow
+///   dividing by zero with `/`, `%` or `~/`.
+/// * `==` and `!=` take any values. Values of different types are never equal, heap objects are
+///   only equal to themselves, and NaN isn't equal to itself.
+/// * `>`, `>=`, `<` and `<=` only take numbers.
+pub fn binary(operator: BinaryOperator, left: Value, right: Value, dialect: Dialect) -> Result<Value, String> {
+    return match operator {
+        BinaryOperator::Add => match (left, right) {
+            (Value::Number(left), Value::Number(right)) => Ok(Value::Number(left + right)),
+            (Value::String(left), Value::String(right)) => Ok(Value::String(left + &right)),
+            (Value::Number(left), Value::String(right)) if dialect == Dialect::Extended => {
+                Ok(Value::String(left.to_string() + &right))
+            }
+            (Value::String(left), Value::Number(right)) if dialect == Dialect::Extended => {
+                Ok(Value::String(left + &right.to_string()))
+            }
+            _ => Err(format!(
+                "Operands of \"{}\" must be two numbers or two strings.",
+                operator.symbol()
+            )),
+        },
+        BinaryOperator::Subtract
+        | BinaryOperator::Multiply
+        | BinaryOperator::Divide
+        | BinaryOperator::Remainder
+        | BinaryOperator::IntegerDivide
+        | BinaryOperator::Power => {
+            let (left, right) = numbers(operator, left, right)?;
+
+            match operator {
+                BinaryOperator::Subtract => Ok(Value::Number(left - right)),
+                BinaryOperator::Multiply => Ok(Value::Number(left * right)),
+                BinaryOperator::Power => Ok(Value::Number(left.powf(right))),
+                _ if right == 0f64 && dialect == Dialect::Extended => Err("Cannot divide by zero.".into()),
+                BinaryOperator::Remainder => Ok(Value::Number(left % right)),
+                BinaryOperator::IntegerDivide => Ok(Value::Number((left / right).trunc())),
+                _ => Ok(Value::Number(left / right)),
+            }
+        }
+        BinaryOperator::Equal => Ok(Value::Boolean(left == right)),
+        BinaryOperator::NotEqual => Ok(Value::Boolean(left != right)),
+        BinaryOperator::Greater
+        | BinaryOperator::GreaterEqual
+        | BinaryOperator::Less
+        | BinaryOperator::LessEqual => {
+            let (left, right) = numbers(operator, left, right)?;
+
+            Ok(Value::Boolean(match operator {
+                BinaryOperator::Greater => left > right,
+                BinaryOperator::GreaterEqual => left >= right,
+                BinaryOperator::Less => left < right,
+                _ => left <= right,
+            }))
+        }
+    };
+}
+
+/// Applies a unary operator to its operand.
+///
+/// * `-` only takes a number.
+/// * `!` takes any value, and returns whether it's falsey.
+pub fn unary(operator: UnaryOperator, operand: Value, dialect: Dialect) -> Result<Value, String> {
+    return match operator {
+        UnaryOperator::Negate => match operand {
+            Value::Number(number) => Ok(Value::Number(-number)),
+            _ => Err(format!("Operand of \"{}\" must be a number.", operator.symbol())),
+        },
+        UnaryOperator::Not => Ok(Value::Boolean(!truthy_or_falsey(&operand, dialect))),
+    };
+}
+
+/// Whether the given value counts as true in a condition. `nil` and `false` are falsey in both
+/// dialects. The extended dialect also treats `0`, `""` and empty lists as falsey.
+pub fn truthy_or_falsey(value: &Value, dialect: Dialect) -> bool {
+    return match value {
+        Value::Nil => false,
+        Value::Boolean(value) => *value,
+        _ if dialect == Dialect::Reference => true,
+        Value::Number(value) => *value != 0f64,
+        Value::String(value) => !value.is_empty(),
+        Value::List(list) => !list.borrow().is_empty(),
+        Value::Function(_) | Value::NativeFunction(_) | Value::Class(_) | Value::Instance(_) => true,
+    };
+}
+
+/// Returns both operands of an operator that only takes numbers.
+fn numbers(operator: BinaryOperator, left: Value, right: Value) -> Result<(f64, f64), String> {
+    return match (left, right) {
+        (Value::Number(left), Value::Number(right)) => Ok((left, right)),
+        _ => Err(format!("Operands of \"{}\" must be two numbers.", operator.symbol())),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+
+    use crate::class::{Class, Instance};
+    use crate::environment::Environment;
+    use crate::function::{Function, NativeFunction};
+    use crate::span::Span;
+    use crate::token::Token;
+
+    use super::*;
+
+    const BINARY_OPERATORS: [BinaryOperator; 13] = [
+        BinaryOperator::Add,
+        BinaryOperator::Subtract,
+        BinaryOperator::Multiply,
+        BinaryOperator::Divide,
+        BinaryOperator::Remainder,
+        BinaryOperator::IntegerDivide,
+        BinaryOperator::Power,
+        BinaryOperator::Equal,
+        BinaryOperator::NotEqual,
+        BinaryOperator::Greater,
+        BinaryOperator::GreaterEqual,
+        BinaryOperator::Less,
+        BinaryOperator::LessEqual,
+    ];
+
+    const DIALECTS: [Dialect; 2] = [Dialect::Extended, Dialect::Reference];
+
+    /// One value of every type, named after its type.
+    fn values() -> Vec<(&'static str, Value)> {
+        let class = Rc::new(Class {
+            name: "A".into(),
+            superclass: None,
+            methods: HashMap::new(),
+        });
+
+        return vec![
+            ("number", Value::Number(2.0)),
+            ("string", Value::String("s".into())),
+            ("boolean", Value::Boolean(true)),
+            ("nil", Value::Nil),
+            (
+                "function",
+                Value::Function(Rc::new(Function {
+                    name: Token {
+                        kind: TokenKind::Identifier,
+                        lexeme: "f".into(),
+                        line: 1,
+                        column: 1,
+                        literal: None,
+                        span: Span::default(),
+                        doc: None,
+                        leading_trivia: Vec::new(),
+                        trailing_trivia: Vec::new(),
+                    },
+                    params: Vec::new(),
+                    body: Rc::new(Vec::new()),
+                    closure: Environment::new_global(),
+                    is_initializer: false,
+                })),
+            ),
+            (
+                "native function",
+                Value::NativeFunction(Rc::new(NativeFunction {
+                    name: "f",
+                    arity: 0,
+                    function: |_| Ok(Value::Nil),
+                })),
+            ),
+            ("class", Value::Class(Rc::clone(&class))),
+            ("instance", Value::Instance(Rc::new(RefCell::new(Instance::new(class))))),
+            ("list", Value::List(Rc::new(RefCell::new(vec![Value::Nil])))),
+        ];
+    }
+
+    mod type_matrix {
+        use super::*;
+
+        #[test]
+        fn arithmetic_and_comparison_only_take_numbers() {
+            for dialect in DIALECTS {
+                for operator in BINARY_OPERATORS {
+                    if matches!(operator, BinaryOperator::Add | BinaryOperator::Equal | BinaryOperator::NotEqual) {
+                        continue;
+                    }
+
+                    for (left_type, left) in values() {
+                        for (right_type, right) in values() {
+                            let result = binary(operator, left.clone(), right.clone(), dialect);
+
+                            if left_type == "number" && right_type == "number" {
+                                assert!(result.is_ok(), "{:?} {} {}", operator, left_type, right_type);
+                            } else {
+                                assert_eq!(
+                                    result,
+                                    Err(format!("Operands of \"{}\" must be two numbers.", operator.symbol())),
+                                    "{:?} {} {}",
+                                    operator,
+                                    left_type,
+                                    right_type
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn add_takes_numbers_and_strings() {
+            for dialect in DIALECTS {
+                for (left_type, left) in values() {
+                    for (right_type, right) in values() {
+                        let accepted = match (left_type, right_type) {
+                            ("number", "number") | ("string", "string") => true,
+                            ("number", "string") | ("string", "number") => dialect == Dialect::Extended,
+                            _ => false,
+                        };
+
+                        let result = binary(BinaryOperator::Add, left.clone(), right.clone(), dialect);
+
+                        if accepted {
+                            assert!(result.is_ok(), "{:?} {} {}", dialect, left_type, right_type);
+                        } else {
+                            assert_eq!(
+                                result,
+                                Err("Operands of \"+\" must be two numbers or two strings.".into()),
+                                "{:?} {} {}",
+                                dialect,
+                                left_type,
+                                right_type
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn equality_takes_anything_and_matches_only_the_same_value() {
+            for dialect in DIALECTS {
+                for (left_index, (_, left)) in values().into_iter().enumerate() {
+                    for (right_index, (_, right)) in values().into_iter().enumerate() {
+                        // Every call of `values` makes new heap objects, so only plain data can
+                        // be equal across two calls.
+                        let equal = left_index == right_index && left_index < 4;
+
+                        assert_eq!(
+                            binary(BinaryOperator::Equal, left.clone(), right.clone(), dialect),
+                            Ok(Value::Boolean(equal))
+                        );
+                        assert_eq!(
+                            binary(BinaryOperator::NotEqual, left.clone(), right.clone(), dialect),
+                            Ok(Value::Boolean(!equal))
+                        );
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn heap_objects_are_equal_to_themselves() {
+            for (value_type, value) in values() {
+                assert_eq!(
+                    binary(BinaryOperator::Equal, value.clone(), value.clone(), Dialect::Extended),
+                    Ok(Value::Boolean(true)),
+                    "{}",
+                    value_type
+                );
+            }
+        }
+
+        #[test]
+        fn negate_only_takes_numbers() {
+            for dialect in DIALECTS {
+                for (value_type, value) in values() {
+                    let result = unary(UnaryOperator::Negate, value, dialect);
+
+                    if value_type == "number" {
+                        assert_eq!(result, Ok(Value::Number(-2.0)));
+                    } else {
+                        assert_eq!(result, Err("Operand of \"-\" must be a number.".into()), "{}", value_type);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn not_takes_anything() {
+            for dialect in DIALECTS {
+                for (value_type, value) in values() {
+                    let expected = !truthy_or_falsey(&value, dialect);
+
+                    assert_eq!(
+                        unary(UnaryOperator::Not, value, dialect),
+                        Ok(Value::Boolean(expected)),
+                        "{}",
+                        value_type
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn compound_assignments_apply_their_operator() {
+        let pairs = [
+            (TokenKind::PlusEqual, BinaryOperator::Add),
+            (TokenKind::MinusEqual, BinaryOperator::Subtract),
+            (TokenKind::StarEqual, BinaryOperator::Multiply),
+            (TokenKind::SlashEqual, BinaryOperator::Divide),
+            (TokenKind::PercentEqual, BinaryOperator::Remainder),
+            (TokenKind::PlusPlus, BinaryOperator::Add),
+            (TokenKind::MinusMinus, BinaryOperator::Subtract),
+        ];
+
+        for (kind, operator) in pairs {
+            assert_eq!(BinaryOperator::from_compound_assignment(&kind), Some(operator));
+        }
+
+        assert_eq!(BinaryOperator::from_compound_assignment(&TokenKind::Equal), None);
+    }
+
+    mod results {
+        use super::*;
+
+        fn number(value: f64) -> Value {
+            return Value::Number(value);
+        }
+
+        fn string(value: &str) -> Value {
+            return Value::String(value.into());
+        }
+
+        #[test]
+        fn arithmetic() {
+            let apply = |operator, left, right| binary(operator, number(left), number(right), Dialect::Extended);
+
+            assert_eq!(apply(BinaryOperator::Add, 1.0, 2.0), Ok(number(3.0)));
+            assert_eq!(apply(BinaryOperator::Subtract, 1.0, 2.0), Ok(number(-1.0)));
+            assert_eq!(apply(BinaryOperator::Multiply, 4.0, 5.0), Ok(number(20.0)));
+            assert_eq!(apply(BinaryOperator::Divide, 10.0, 4.0), Ok(number(2.5)));
+            assert_eq!(apply(BinaryOperator::Remainder, 7.5, 2.0), Ok(number(1.5)));
+            assert_eq!(apply(BinaryOperator::Remainder, -7.0, 2.0), Ok(number(-1.0)));
+            assert_eq!(apply(BinaryOperator::IntegerDivide, 7.0, 2.0), Ok(number(3.0)));
+            assert_eq!(apply(BinaryOperator::IntegerDivide, -7.0, 2.0), Ok(number(-3.0)));
+            assert_eq!(apply(BinaryOperator::Power, 2.0, 10.0), Ok(number(1024.0)));
+            assert_eq!(apply(BinaryOperator::Power, 4.0, -0.5), Ok(number(0.5)));
+            assert_eq!(apply(BinaryOperator::Power, 0.0, 0.0), Ok(number(1.0)));
+        }
+
+        #[test]
+        fn concatenation() {
+            let apply = |left, right, dialect| binary(BinaryOperator::Add, left, right, dialect);
+
+            assert_eq!(apply(string("a"), string("b"), Dialect::Reference), Ok(string("ab")));
+            assert_eq!(apply(number(1.0), string("a"), Dialect::Extended), Ok(string("1a")));
+            assert_eq!(apply(string("a"), number(1.5), Dialect::Extended), Ok(string("a1.5")));
+        }
+
+        #[test]
+        fn division_by_zero() {
+            for operator in [BinaryOperator::Divide, BinaryOperator::Remainder, BinaryOperator::IntegerDivide] {
+                assert_eq!(
+                    binary(operator, number(1.0), number(0.0), Dialect::Extended),
+                    Err("Cannot divide by zero.".into()),
+                    "{:?}",
+                    operator
+                );
+            }
+
+            let apply = |operator| binary(operator, number(1.0), number(0.0), Dialect::Reference);
+
+            assert_eq!(apply(BinaryOperator::Divide), Ok(number(f64::INFINITY)));
+            assert_eq!(apply(BinaryOperator::IntegerDivide), Ok(number(f64::INFINITY)));
+            assert!(matches!(apply(BinaryOperator::Remainder), Ok(Value::Number(n)) if n.is_nan()));
+        }
+
+        #[test]
+        fn comparison() {
+            let apply = |operator, left, right| binary(operator, number(left), number(right), Dialect::Extended);
+
+            assert_eq!(apply(BinaryOperator::Greater, 2.0, 1.0), Ok(Value::Boolean(true)));
+            assert_eq!(apply(BinaryOperator::GreaterEqual, 1.0, 1.0), Ok(Value::Boolean(true)));
+            assert_eq!(apply(BinaryOperator::Less, 2.0, 1.0), Ok(Value::Boolean(false)));
+            assert_eq!(apply(BinaryOperator::LessEqual, 2.0, 1.0), Ok(Value::Boolean(false)));
+            assert_eq!(apply(BinaryOperator::Less, f64::NAN, 1.0), Ok(Value::Boolean(false)));
+        }
+
+        #[test]
+        fn nan_is_not_equal_to_itself() {
+            assert_eq!(
+                binary(BinaryOperator::Equal, number(f64::NAN), number(f64::NAN), Dialect::Extended),
+                Ok(Value::Boolean(false))
+            );
+        }
+
+        #[test]
+        fn truthiness_per_dialect() {
+            let falsey_in_extended = [number(0.0), string(""), Value::List(Rc::new(RefCell::new(Vec::new())))];
+
+            for value in falsey_in_extended {
+                assert!(!truthy_or_falsey(&value, Dialect::Extended), "{}", value);
+                assert!(truthy_or_falsey(&value, Dialect::Reference), "{}", value);
+            }
+
+            for dialect in DIALECTS {
+                assert!(!truthy_or_falsey(&Value::Nil, dialect));
+                assert!(!truthy_or_falsey(&Value::Boolean(false), dialect));
+
+                for (value_type, value) in values() {
+                    if matches!(value_type, "function" | "native function" | "class" | "instance" | "list") {
+                        assert!(truthy_or_falsey(&value, dialect), "{:?} {}", dialect, value_type);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,41 +1,188 @@
 use crate::expressions::Expr;
 use crate::language_error::Error;
 use crate::literal::Literal;
-use crate::report_error;
+use crate::span::Span;
 use crate::stmt::Stmt;
 use crate::token::Token;
 use crate::token_kinds::TokenKind;
 
+/// The maximum number of parameters a function can declare, and arguments a call can pass.
+const MAX_ARGUMENTS: usize = 255;
+
+/// How tightly an operator holds on to its operands. An operand between two operators goes to the
+/// one with the higher precedence.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+enum Precedence {
+    /// Looser than every operator. An expression parsed at this precedence takes all of them.
+    Lowest,
+    Assignment,
+    Conditional,
+    Or,
+    And,
+    Equality,
+    Comparison,
+    Term,
+    Factor,
+    Unary,
+    Exponent,
+    Call,
+}
+
+impl Precedence {
+    /// Returns the precedence one level tighter than this one.
+    fn next(self) -> Precedence {
+        return match self {
+            Precedence::Lowest => Precedence::Assignment,
+            Precedence::Assignment => Precedence::Conditional,
+            Precedence::Conditional => Precedence::Or,
+            Precedence::Or => Precedence::And,
+            Precedence::And => Precedence::Equality,
+            Precedence::Equality => Precedence::Comparison,
+            Precedence::Comparison => Precedence::Term,
+            Precedence::Term => Precedence::Factor,
+            Precedence::Factor => Precedence::Unary,
+            Precedence::Unary => Precedence::Exponent,
+            Precedence::Exponent | Precedence::Call => Precedence::Call,
+        };
+    }
+}
+
+/// Which operator gets an operand between two operators of the same precedence.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Associativity {
+    /// `a - b - c` is `(a - b) - c`.
+    Left,
+    /// `a = b = c` is `a = (b = c)`, `a ? b : c ? d : e` is `a ? b : (c ? d : e)`, and `a ** b ** c`
+    /// is `a ** (b ** c)`.
+    Right,
+}
+
+/// The expression an operator that follows its left operand makes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Infix {
+    /// `target = value` and the compound assignments like `target += value`, which assign to a
+    /// variable or a property.
+    Assignment,
+    /// `condition ? then : else`, which only evaluates one of its branches.
+    Conditional,
+    /// `or` and `and`, which short-circuit.
+    Logical,
+    /// The arithmetic, comparison and equality operators.
+    Binary,
+    /// `callee(arguments)`.
+    Call,
+    /// `object.name`.
+    Property,
+    /// `target++` and `target--`, which evaluate to the target's value before the update.
+    Increment,
+}
+
+/// An operator that comes after its left operand, either a binary operator or a postfix form.
+#[derive(Debug, Clone, Copy)]
+struct InfixOperator {
+    kind: Infix,
+    precedence: Precedence,
+    associativity: Associativity,
+}
+
+/// The binding power table. Returns what the token does when it follows an operand, or `None` if
+/// it isn't an operator and ends the expression.
+fn infix_operator(kind: &TokenKind) -> Option<InfixOperator> {
+    let (kind, precedence, associativity) = match kind {
+        TokenKind::Equal
+        | TokenKind::PlusEqual
+        | TokenKind::MinusEqual
+        | TokenKind::StarEqual
+        | TokenKind::SlashEqual
+        | TokenKind::PercentEqual => (Infix::Assignment, Precedence::Assignment, Associativity::Right),
+        TokenKind::Question => (Infix::Conditional, Precedence::Conditional, Associativity::Right),
+        TokenKind::Or => (Infix::Logical, Precedence::Or, Associativity::Left),
+        TokenKind::And => (Infix::Logical, Precedence::And, Associativity::Left),
+        TokenKind::BangEqual | TokenKind::EqualEqual => {
+            (Infix::Binary, Precedence::Equality, Associativity::Left)
+        }
+        TokenKind::Greater | TokenKind::GreaterEqual | TokenKind::Less | TokenKind::LessEqual => {
+            (Infix::Binary, Precedence::Comparison, Associativity::Left)
+        }
+        TokenKind::Minus | TokenKind::Plus => (Infix::Binary, Precedence::Term, Associativity::Left),
+        TokenKind::Slash | TokenKind::Star | TokenKind::Percent | TokenKind::TildeSlash => {
+            (Infix::Binary, Precedence::Factor, Associativity::Left)
+        }
+        // Tighter than the prefix operators, so `-2 ** 2` is `-(2 ** 2)`.
+        TokenKind::StarStar => (Infix::Binary, Precedence::Exponent, Associativity::Right),
+        TokenKind::LeftParen => (Infix::Call, Precedence::Call, Associativity::Left),
+        TokenKind::Dot => (Infix::Property, Precedence::Call, Associativity::Left),
+        TokenKind::PlusPlus | TokenKind::MinusMinus => (Infix::Increment, Precedence::Call, Associativity::Left),
+        _ => return None,
+    };
+
+    return Some(InfixOperator {
+        kind,
+        precedence,
+        associativity,
+    });
+}
+
+/// Returns the precedence the operand of a prefix operator is parsed at, or `None` if the token
+/// isn't a prefix operator.
+fn prefix_operator(kind: &TokenKind) -> Option<Precedence> {
+    return match kind {
+        TokenKind::Bang | TokenKind::Minus => Some(Precedence::Unary),
+        _ => None,
+    };
+}
+
 /// The Parser is responsible for taking a list of tokens and turning them into an AST.
-/// It reports (doesn't return) any errors that occur during parsing.
+/// It doesn't print anything. The errors that occur during parsing are returned alongside the AST,
+/// for the caller to report.
 ///
 /// ## Grammar:
 /// * program               → complete_statement* EOF ;
-/// * declaration           → varDecl | statement ";" ;
+/// * declaration           → classDecl | varDecl | funDecl | statement ";" ;
+/// * classDecl             → "class" IDENTIFIER ( "<" IDENTIFIER )? "{" function* "}" ;
 /// * varDecl               → "var" IDENTIFIER ("=" expression)? ";" ;
-/// * statement             → ifStmt | printStmt | blockStmt | expressionStmt ";" ;
-/// * ifStmt                → "if" expression "{" statement* "}" ( "else" "{" statement* "}" )? ;
+/// * funDecl               → "fun" function ;
+/// * function              → IDENTIFIER "(" parameters? ")" blockStmt ;
+/// * parameters            → IDENTIFIER ( "," IDENTIFIER )* ;
+/// * statement             → ifStmt | loopStmt | breakStmt | continueStmt | printStmt | returnStmt | blockStmt | expressionStmt ";" ;
+/// * ifStmt                → "if" expression blockStmt ( "else" "if" expression blockStmt )* ( "else" blockStmt )? ;
+/// * loopStmt              → ( IDENTIFIER ":" )? ( whileStmt | forStmt ) ;
+/// * whileStmt             → "while" expression blockStmt ;
+/// * forStmt               → "for" ( expression | ( varDecl | expressionStmt | ";" ) expression? ";" expression? ) blockStmt ;
+/// * breakStmt             → "break" IDENTIFIER? ";" ;
+/// * continueStmt          → "continue" IDENTIFIER? ";" ;
 /// * printStmt             → "print" expression ;
+/// * returnStmt            → "return" expression? ";" ;
 /// * blockStmt             → "{" declaration* "}" ;
 /// * expressionStmt        → expression ";" ;
 /// * expression            → assignment ;
-/// * assignment            → IDENTIFIER "=" logical_or ;
+/// * assignment            → ( call "." IDENTIFIER | IDENTIFIER ) ( "=" | "+=" | "-=" | "*=" | "/=" | "%=" ) assignment | conditional ;
+/// * conditional           → logical_or ( "?" expression ":" conditional )? ;
 /// * logical_or            → logical_and ("or" logical_and )* ;
 /// * logical_and           → equality ("and" equality )* ;
 /// * equality              → comparison ( ( "!=" | "==" ) comparison )* ;
 /// * comparison            → term ( ( ">" | ">=" | "<" | "<=" ) term )* ;
 /// * term                  → factor ( ( "-" | "+" ) factor )* ;
-/// * factor                → unary ( ( "/" | "*" ) unary )* ;
-/// * unary                 → ( "!" | "-" ) unary | primary ;
-/// * primary               → NUMBER | STRING | "true" | "false" | "nil" | "(" expression ")" | IDENTIFIER ;
+/// * factor                → unary ( ( "/" | "*" | "%" | "~/" ) unary )* ;
+/// * unary                 → ( "!" | "-" ) unary | ( "++" | "--" ) unary | exponent ;
+/// * exponent              → call ( "**" unary )? ;
+/// * call                  → primary ( "(" arguments? ")" | "." IDENTIFIER | "++" | "--" )* ;
+/// * arguments             → expression ( "," expression )* ;
+/// * primary               → NUMBER | STRING | interpolation | "true" | "false" | "nil" | "self" | "(" expression ")" | IDENTIFIER | "super" "." IDENTIFIER ;
+/// * interpolation         → ( INTERPOLATION expression )+ STRING ;
 ///
 /// Note:
 /// * `(a)*` means 0 or more of a.
 /// * `?` means that it is optional.
+/// * The rules from `assignment` to `call` aren't functions of their own. The operators in them
+///   are looked up in `infix_operator` and `prefix_operator`, which give their precedence.
 pub struct Parser<'a> {
     tokens: &'a Vec<Token>,
     current: usize,
-    pub errors: Vec<Error>,
+    errors: Vec<Error>,
+    /// The labels of the loops enclosing the statement being parsed, innermost last. Unlabeled
+    /// loops are `None`.
+    loops: Vec<Option<String>>,
 }
 
 impl<'a> Parser<'a> {
@@ -44,480 +191,948 @@ impl<'a> Parser<'a> {
             tokens,
             current: 0,
             errors: Vec::new(),
+            loops: Vec::new(),
         };
     }
 
-    /// Parses the tokens into an AST. Reports any errors that occur during parsing and populates
-    /// the error list.
-    pub fn parse(&mut self) -> Vec<Stmt> {
+    /// Parses the tokens into an AST. Returns it together with the errors that occurred during
+    /// parsing. The AST can't be run if there are any.
+    ///
+    /// A syntax error discards the declaration it's in. Parsing picks up again from the next
+    /// statement, so a single run finds all the syntax errors in the source.
+    pub fn parse(&mut self) -> (Vec<Stmt>, Vec<Error>) {
         let mut statements = Vec::<Stmt>::new();
 
         while !self.is_at_end() {
-            statements.push(self.declaration_rule());
+            if let Some(statement) = self.declaration() {
+                statements.push(statement);
+            }
         }
 
-        return statements;
+        return (statements, std::mem::take(&mut self.errors));
     }
 
-    fn declaration_rule(&mut self) -> Stmt {
+    /// Parses a declaration. On a syntax error, records it, skips to the next statement and
+    /// returns `None`.
+    fn declaration(&mut self) -> Option<Stmt> {
+        let start = self.current;
+
+        return match self.declaration_rule() {
+            Ok(statement) => Some(statement),
+            Err(err) => {
+                self.errors.push(err);
+                self.synchronise(start);
+
+                // Always move past the token the error was found at, or we'd find it again.
+                if self.current == start {
+                    self.advance();
+                }
+
+                None
+            }
+        };
+    }
+
+    fn declaration_rule(&mut self) -> Result<Stmt, Error> {
         return if self.peek().kind == TokenKind::Var {
-            let ret = self.var_declaration_rule();
+            let ret = self.var_declaration_rule()?;
 
-            self.consume_semicolon();
+            self.consume_semicolon()?;
 
-            ret
+            Ok(ret)
+        } else if self.peek().kind == TokenKind::Fun {
+            let doc = self.advance().doc.clone(); // current is the function name.
+
+            self.function_rule(doc)
+        } else if self.peek().kind == TokenKind::Class {
+            self.class_declaration_rule()
         } else {
             self.statement_rule()
         };
     }
 
-    fn var_declaration_rule(&mut self) -> Stmt {
-        self.advance(); // current is variable name.
+    fn class_declaration_rule(&mut self) -> Result<Stmt, Error> {
+        let doc = self.advance().doc.clone(); // current is the class name.
 
         if self.peek().kind != TokenKind::Identifier {
-            let err = Error::new(
-                "Expected identifier after \"var\".".into(),
-                Some(self.previous().line),
-                self.previous().column,
-                None,
-            );
+            return Err(self.error("Expected class name."));
+        }
 
-            report_error(&err);
+        let name = self.advance().clone();
 
-            self.errors.push(err);
+        let superclass = if self.peek().kind == TokenKind::Less {
+            self.advance();
 
-            self.synchronise();
+            if self.peek().kind != TokenKind::Identifier {
+                return Err(self.error("Expected superclass name."));
+            }
 
-            // Not sure what to return here. We need to return a Stmt, but we don't have one.
-            return Stmt::VarDeclStmt {
-                name: self.previous().clone(),
-                initializer: Expr::LiteralExpression {
-                    value: Some(Literal::Nil),
-                },
-            };
+            Some(Box::new(Expr::VariableResolutionExpression {
+                name: self.advance().clone(),
+                binding: None,
+            }))
+        } else {
+            None
+        };
+
+        if self.peek().kind != TokenKind::LeftBrace {
+            return Err(self.error("Expected \"{\" before class body."));
+        }
+
+        self.advance();
+
+        let mut methods = Vec::<Stmt>::new();
+        while self.peek().kind != TokenKind::RightBrace && !self.is_at_end() {
+            let doc = self.peek().doc.clone();
+            methods.push(self.function_rule(doc)?);
+        }
+
+        if self.peek().kind != TokenKind::RightBrace {
+            return Err(self.error("Expected \"}\" after class body."));
+        }
+
+        self.advance();
+
+        return Ok(Stmt::ClassStmt {
+            name,
+            methods,
+            superclass,
+            doc,
+        });
+    }
+
+    /// Parses the name, parameters and body of a function. Expects the current token to be the
+    /// function name. `doc` is the doc comment written before the function.
+    fn function_rule(&mut self, doc: Option<String>) -> Result<Stmt, Error> {
+        if self.peek().kind != TokenKind::Identifier {
+            return Err(self.error("Expected function name."));
+        }
+
+        let name = self.advance().clone();
+
+        if self.peek().kind != TokenKind::LeftParen {
+            return Err(self.error("Expected \"(\" after function name."));
+        }
+
+        self.advance();
+
+        let mut params = Vec::<Token>::new();
+        if self.peek().kind != TokenKind::RightParen {
+            loop {
+                if params.len() >= MAX_ARGUMENTS {
+                    // The parser isn't confused by this one, so it keeps going.
+                    let err = self.error(format!("Can't have more than {} parameters.", MAX_ARGUMENTS).as_str());
+                    self.errors.push(err);
+                }
+
+                if self.peek().kind != TokenKind::Identifier {
+                    return Err(self.error("Expected parameter name."));
+                }
+
+                params.push(self.advance().clone());
+
+                if self.peek().kind != TokenKind::Comma {
+                    break;
+                }
+
+                self.advance();
+            }
         }
 
-        let var_name = self.peek().lexeme.clone();
+        if self.peek().kind != TokenKind::RightParen {
+            return Err(self.error("Expected \")\" after parameters."));
+        }
+
+        self.advance();
+
+        if self.peek().kind != TokenKind::LeftBrace {
+            return Err(self.error("Expected \"{\" before function body."));
+        }
 
-        self.advance(); // current is "=" or ";"
+        // Loops outside the function can't be targeted by `break` or `continue` inside it.
+        let enclosing_loops = std::mem::take(&mut self.loops);
+        let body = self.block_rule();
+        self.loops = enclosing_loops;
+
+        return Ok(Stmt::FunctionStmt {
+            name,
+            params,
+            body: body?,
+            doc,
+        });
+    }
+
+    fn var_declaration_rule(&mut self) -> Result<Stmt, Error> {
+        let doc = self.advance().doc.clone(); // current is variable name.
+
+        if self.peek().kind != TokenKind::Identifier {
+            return Err(self.error("Expected identifier after \"var\"."));
+        }
+
+        let name = self.advance().clone(); // current is "=" or ";"
 
         let value;
         if self.peek().kind == TokenKind::Equal {
             self.advance();
 
-            value = self.expression_rule();
+            value = self.expression_rule()?;
         } else if self.peek().kind == TokenKind::Semicolon {
-            value = Box::new(Expr::LiteralExpression { value: None });
+            // An uninitialized variable is `nil`. The value points at the variable's name.
+            value = Box::new(Expr::LiteralExpression {
+                value: None,
+                span: self.previous().span,
+            });
         } else {
-            let err = Error::new(
-                "Expected \"=\" or \";\" after variable declaration.".into(),
-                Some(self.previous().line),
-                self.previous().column,
-                None,
-            );
-
-            report_error(&err);
-
-            self.errors.push(err);
-
-            self.synchronise();
-
-            // Not sure what to return here. We need to return a Stmt, but we don't have one.
-            return Stmt::VarDeclStmt {
-                name: self.previous().clone(),
-                initializer: Expr::LiteralExpression {
-                    value: Some(Literal::Nil),
-                },
-            };
+            return Err(self.error("Expected \"=\" or \";\" after variable declaration."));
         }
 
-        return Stmt::VarDeclStmt {
-            name: Token {
-                kind: TokenKind::Identifier,
-                lexeme: var_name,
-                line: self.previous().line,
-                column: self.previous().column,
-                literal: None,
-            },
-            initializer: *value,
-        };
+        return Ok(Stmt::VarDeclStmt {
+            name,
+            initializer: value,
+            doc,
+        });
     }
 
     /// Parses a statement based on the current token.
-    fn statement_rule(&mut self) -> Stmt {
+    fn statement_rule(&mut self) -> Result<Stmt, Error> {
         if self.peek().kind == TokenKind::If {
             return self.if_statement_rule();
+        } else if self.peek().kind == TokenKind::While {
+            return self.while_statement_rule(None);
+        } else if self.peek().kind == TokenKind::For {
+            return self.for_statement_rule(None);
+        } else if self.peek().kind == TokenKind::Identifier
+            && self.peek_next().kind == TokenKind::Colon
+        {
+            return self.labeled_statement_rule();
+        } else if self.peek().kind == TokenKind::Break || self.peek().kind == TokenKind::Continue {
+            return self.break_or_continue_statement_rule();
         } else if self.peek().kind == TokenKind::Print {
-            let ret = self.print_statement_rule();
+            let ret = self.print_statement_rule()?;
 
-            self.consume_semicolon();
+            self.consume_semicolon()?;
 
-            return ret;
+            return Ok(ret);
+        } else if self.peek().kind == TokenKind::Return {
+            return self.return_statement_rule();
         } else if self.peek().kind == TokenKind::LeftBrace {
             // Block statement.
             return self.block_statement_rule();
         } else if self.peek_next().kind == TokenKind::Equal {
             // Assignment statement.
             let ret = Stmt::AssignmentStmt {
-                expression: self.assignment_rule(),
+                expression: self.expression_rule()?,
             };
 
-            self.consume_semicolon();
+            self.consume_semicolon()?;
 
-            return ret;
+            return Ok(ret);
         } else {
             // Expression statement. An expression wrapped in a statement.
             return self.expression_statement_rule();
         }
     }
 
-    fn block_statement_rule(&mut self) -> Stmt {
+    fn block_statement_rule(&mut self) -> Result<Stmt, Error> {
+        return Ok(Stmt::BlockStmt {
+            statements: self.block_rule()?,
+        });
+    }
+
+    /// Parses the declarations between a pair of braces. Expects the current token to be "{".
+    /// Syntax errors inside the block are recorded and skipped, like at the top level.
+    fn block_rule(&mut self) -> Result<Vec<Stmt>, Error> {
         self.advance();
 
         let mut statements = Vec::<Stmt>::new();
 
         while self.peek().kind != TokenKind::RightBrace && !self.is_at_end() {
-            statements.push(self.declaration_rule()); // Test this against complete_statement_rule().
+            if let Some(statement) = self.declaration() {
+                statements.push(statement);
+            }
         }
 
         if self.peek().kind != TokenKind::RightBrace {
-            let err = Error::new(
-                "Expected \"}\" after block.".into(),
-                Some(self.previous().line),
-                self.previous().column,
-                None,
-            );
-
-            report_error(&err);
-
-            self.errors.push(err);
-        } else {
-            self.advance();
+            return Err(self.error("Expected \"}\" after block."));
         }
 
-        return Stmt::BlockStmt { statements };
+        self.advance();
+
+        return Ok(statements);
     }
 
     /// Rule for tradition if statement.
-    fn if_statement_rule(&mut self) -> Stmt {
+    fn if_statement_rule(&mut self) -> Result<Stmt, Error> {
         // handle `if` branch.
         self.advance();
 
-        let expr_condition = self.expression_rule();
+        let expr_condition = self.expression_rule()?;
 
         if self.peek().kind != TokenKind::LeftBrace {
-            let err_msg = "Expected \"{\" after block.".to_string();
-            let err = Error::new(
-                err_msg.clone(),
-                Some(self.previous().line),
-                self.previous().column,
-                None,
-            );
-
-            report_error(&err);
-
-            self.errors.push(err);
-
-            return Stmt::None {err: err_msg};
+            return Err(self.error("Expected \"{\" after block."));
         }
 
-        let if_body = Box::new(self.block_statement_rule());
+        let if_body = Box::new(self.block_statement_rule()?);
 
-        let else_branch;
         let mut else_if_branches = vec![];
 
         // Handle optional (multiple) `else if` branches.
-        while !self.is_at_end() && self.peek().kind == TokenKind::ElseIf {
+        while self.peek().kind == TokenKind::Else && self.peek_next().kind == TokenKind::If {
+            self.advance(); // Advances from "else" to "if".
             self.advance();
-            let else_if_expr_condition = self.expression_rule();
+            let else_if_expr_condition = self.expression_rule()?;
 
             if self.peek().kind != TokenKind::LeftBrace {
-                let err_msg = "Expected \"{\" after block.".to_string();
-                let err = Error::new(
-                    err_msg.clone(),
-                    Some(self.previous().line),
-                    self.previous().column,
-                    None,
-                );
-
-                report_error(&err);
-
-                self.errors.push(err);
-
-            } else {
-                let else_if_then_branch = Box::new(self.block_statement_rule());
-                else_if_branches.push(Box::new(Stmt::IfStmt {
-                    condition: else_if_expr_condition,
-                    then_branch: else_if_then_branch,
-                    else_if_branches: Vec::new(), // Empty vec denotes None.
-                    else_branch: None, // Because this is a else_if for an outer if; It should never include an else (or an else if.)
-                }));
+                return Err(self.error("Expected \"{\" after block."));
             }
-        } 
+
+            let else_if_then_branch = Box::new(self.block_statement_rule()?);
+            else_if_branches.push(Box::new(Stmt::IfStmt {
+                condition: else_if_expr_condition,
+                then_branch: else_if_then_branch,
+                else_if_branches: Vec::new(), // Empty vec denotes None.
+                else_branch: None, // Because this is a else_if for an outer if; It should never include an else (or an else if.)
+            }));
+        }
 
         // Handle optional `else` branch.
-        if !self.is_at_end() && self.peek().kind == TokenKind::Else {
+        let else_branch = if self.peek().kind == TokenKind::Else {
             self.advance(); // Advances from "else" to "{"
 
-            else_branch = Some(Box::new(self.block_statement_rule()));
+            if self.peek().kind != TokenKind::LeftBrace {
+                return Err(self.error("Expected \"{\" after \"else\"."));
+            }
+
+            Some(Box::new(self.block_statement_rule()?))
         } else {
-            else_branch = None
+            None
         };
 
-        return Stmt::IfStmt {
+        return Ok(Stmt::IfStmt {
             condition: expr_condition,
             then_branch: if_body,
             else_if_branches,
             else_branch,
-        };
+        });
     }
 
-    fn print_statement_rule(&mut self) -> Stmt {
-        self.advance();
+    /// Rule for `label: while ...` and `label: for ...`.
+    fn labeled_statement_rule(&mut self) -> Result<Stmt, Error> {
+        let label = self.advance().clone();
 
-        let value = self.expression_rule();
+        self.advance(); // current is the loop keyword.
 
-        return Stmt::PrintStmt { expression: value };
+        if self.peek().kind == TokenKind::While {
+            return self.while_statement_rule(Some(label));
+        } else if self.peek().kind == TokenKind::For {
+            return self.for_statement_rule(Some(label));
+        }
+
+        return Err(self.error("Only loops can be labeled."));
     }
 
-    fn expression_statement_rule(&mut self) -> Stmt {
-        let expr = self.expression_rule();
+    fn while_statement_rule(&mut self, label: Option<Token>) -> Result<Stmt, Error> {
+        self.advance();
 
-        self.consume_semicolon();
+        let condition = self.expression_rule()?;
 
-        return Stmt::ExpressionStmt { expression: expr };
-    }
+        if self.peek().kind != TokenKind::LeftBrace {
+            return Err(self.error("Expected \"{\" after loop condition."));
+        }
 
-    fn expression_rule(&mut self) -> Box<Expr> {
-        return self.assignment_rule();
+        let body = self.loop_body_rule(&label)?;
+
+        return Ok(Stmt::WhileStmt {
+            condition,
+            body,
+            increment: None,
+            per_iteration_scope: false,
+            label,
+        });
     }
 
-    fn assignment_rule(&mut self) -> Box<Expr> {
-        let expr = self.logical_or_rule();
+    /// Parses the block of a loop, keeping track of the loop so `break` and `continue` can be
+    /// checked against it.
+    fn loop_body_rule(&mut self, label: &Option<Token>) -> Result<Box<Stmt>, Error> {
+        self.loops.push(label.as_ref().map(|label| label.lexeme.clone()));
+        let body = self.block_statement_rule();
+        self.loops.pop();
 
-        if self.peek().kind == TokenKind::Equal {
-            let var_name = self.previous().clone();
+        return Ok(Box::new(body?));
+    }
 
-            self.advance();
+    fn break_or_continue_statement_rule(&mut self) -> Result<Stmt, Error> {
+        let keyword = self.advance().clone();
+
+        let label = if self.peek().kind == TokenKind::Identifier {
+            Some(self.advance().clone())
+        } else {
+            None
+        };
 
-            let value = self.assignment_rule();
+        self.consume_semicolon()?;
 
-            return Box::new(Expr::AssignmentExpression {
-                name: var_name,
-                value,
-            });
+        // The statement itself is well-formed, so these don't need to skip anything.
+        if self.loops.is_empty() {
+            let err = self.error_at(&keyword, format!("Can't use \"{}\" outside of a loop.", keyword.lexeme).as_str());
+            self.errors.push(err);
+        } else if let Some(label) = &label {
+            if !self.loops.contains(&Some(label.lexeme.clone())) {
+                let err = self.error_at(label, format!("No enclosing loop is labeled \"{}\".", label.lexeme).as_str());
+                self.errors.push(err);
+            }
         }
 
-        return expr;
+        return Ok(if keyword.kind == TokenKind::Break {
+            Stmt::BreakStmt { keyword, label }
+        } else {
+            Stmt::ContinueStmt { keyword, label }
+        });
     }
 
-    fn logical_or_rule(&mut self) -> Box<Expr> {
-        let mut expr = self.logical_and_rule();
+    /// Rule for both the condition-only `for condition { ... }` loop and the C-style
+    /// `for initializer; condition; increment { ... }` loop. Both are desugared into a `WhileStmt`.
+    fn for_statement_rule(&mut self, label: Option<Token>) -> Result<Stmt, Error> {
+        self.advance();
 
-        if self.peek().kind == TokenKind::Or {
+        let initializer = if self.peek().kind == TokenKind::Semicolon {
             self.advance();
-            expr = Box::new(Expr::LogicalExpression { 
-                left: expr,
-                operator: self.previous().to_owned(),
-                right: self.logical_and_rule(),
-            });
+
+            None
+        } else if self.peek().kind == TokenKind::Var {
+            let ret = self.var_declaration_rule()?;
+
+            self.consume_semicolon()?;
+
+            Some(ret)
+        } else {
+            let expr = self.expression_rule()?;
+
+            // An expression directly followed by the body is the condition of a condition-only loop.
+            if self.peek().kind == TokenKind::LeftBrace {
+                return Ok(Stmt::WhileStmt {
+                    condition: expr,
+                    body: self.loop_body_rule(&label)?,
+                    increment: None,
+                    per_iteration_scope: false,
+                    label,
+                });
+            }
+
+            self.consume_semicolon()?;
+
+            Some(self.expression_to_statement(expr))
+        };
+
+        let condition = if self.peek().kind == TokenKind::Semicolon {
+            Box::new(Expr::LiteralExpression {
+                value: Some(Literal::Boolean(true)),
+                span: self.peek().span,
+            })
+        } else {
+            self.expression_rule()?
+        };
+
+        self.consume_semicolon()?;
+
+        let increment = if self.peek().kind == TokenKind::LeftBrace {
+            None
+        } else {
+            let expr = self.expression_rule()?;
+
+            Some(Box::new(self.expression_to_statement(expr)))
+        };
+
+        if self.peek().kind != TokenKind::LeftBrace {
+            return Err(self.error("Expected \"{\" after for clauses."));
         }
 
-        return expr;
+        let while_stmt = Stmt::WhileStmt {
+            condition,
+            body: self.loop_body_rule(&label)?,
+            increment,
+            per_iteration_scope: matches!(initializer, Some(Stmt::VarDeclStmt { .. })),
+            label,
+        };
+
+        // The initializer gets its own scope so the loop variable doesn't outlive the loop.
+        return Ok(match initializer {
+            Some(initializer) => Stmt::BlockStmt {
+                statements: vec![initializer, while_stmt],
+            },
+            None => while_stmt,
+        });
     }
 
-    fn logical_and_rule(&mut self) -> Box<Expr> {
-        let mut expr = self.equality_rule();
+    /// Wraps an expression that was parsed where a statement was expected. Assignments become
+    /// `AssignmentStmt`s just like they do at the start of a statement.
+    fn expression_to_statement(&self, expr: Box<Expr>) -> Stmt {
+        return if let Expr::AssignmentExpression { .. } = expr.as_ref() {
+            Stmt::AssignmentStmt { expression: expr }
+        } else {
+            Stmt::ExpressionStmt { expression: expr }
+        };
+    }
 
-        if self.peek().kind == TokenKind::And {
-            self.advance();
-            expr = Box::new(Expr::LogicalExpression { 
-                left: expr,
-                operator: self.previous().to_owned(),
-                right: self.logical_and_rule(),
-            });
-        }
+    fn print_statement_rule(&mut self) -> Result<Stmt, Error> {
+        self.advance();
+
+        let value = self.expression_rule()?;
 
-        return expr;
+        return Ok(Stmt::PrintStmt { expression: value });
     }
 
-    fn equality_rule(&mut self) -> Box<Expr> {
-        let mut expr = self.comparison_rule();
+    fn return_statement_rule(&mut self) -> Result<Stmt, Error> {
+        let keyword = self.advance().clone();
 
-        while self.tokens[self.current].kind == TokenKind::BangEqual
-            || self.tokens[self.current].kind == TokenKind::EqualEqual
-        {
-            self.advance();
+        let value = if self.peek().kind != TokenKind::Semicolon {
+            Some(self.expression_rule()?)
+        } else {
+            None
+        };
 
-            expr = Box::new(Expr::BinaryExpression {
-                left: expr,
-                operator: self.previous().clone(),
-                right: self.comparison_rule(),
-            });
-        }
+        self.consume_semicolon()?;
 
-        return expr;
+        return Ok(Stmt::ReturnStmt { keyword, value });
     }
 
-    fn comparison_rule(&mut self) -> Box<Expr> {
-        let mut expr = self.term_rule();
+    fn expression_statement_rule(&mut self) -> Result<Stmt, Error> {
+        let expr = self.expression_rule()?;
 
-        while self.tokens[self.current].kind == TokenKind::Greater
-            || self.tokens[self.current].kind == TokenKind::GreaterEqual
-            || self.tokens[self.current].kind == TokenKind::Less
-            || self.tokens[self.current].kind == TokenKind::LessEqual
-        {
-            self.advance();
+        self.consume_semicolon()?;
 
-            expr = Box::new(Expr::BinaryExpression {
-                left: expr,
-                operator: self.previous().clone(),
-                right: self.term_rule(),
-            });
+        return Ok(Stmt::ExpressionStmt { expression: expr });
+    }
+
+    fn expression_rule(&mut self) -> Result<Box<Expr>, Error> {
+        return self.expression_at(Precedence::Lowest);
+    }
+
+    /// Parses an expression whose operators bind at least as tightly as `precedence`. The first
+    /// operator that binds looser ends it and is left for the caller.
+    fn expression_at(&mut self, precedence: Precedence) -> Result<Box<Expr>, Error> {
+        let mut expr = self.prefix_rule()?;
+
+        while let Some(operator) = infix_operator(&self.peek().kind) {
+            if operator.precedence < precedence {
+                break;
+            }
+
+            expr = self.infix_rule(expr, operator)?;
         }
 
-        return expr;
+        return Ok(expr);
     }
 
-    fn term_rule(&mut self) -> Box<Expr> {
-        let mut expr = self.factor_rule();
+    /// Parses the start of an expression: a prefix operator and its operand, or a primary.
+    fn prefix_rule(&mut self) -> Result<Box<Expr>, Error> {
+        if matches!(self.peek().kind, TokenKind::PlusPlus | TokenKind::MinusMinus) {
+            let operator = self.advance().clone();
+            let target = self.expression_at(Precedence::Unary)?;
 
-        while self.tokens[self.current].kind == TokenKind::Minus
-            || self.tokens[self.current].kind == TokenKind::Plus
-        {
-            self.advance();
+            return Ok(self.increment(target, operator, false));
+        }
 
-            expr = Box::new(Expr::BinaryExpression {
-                left: expr,
-                operator: self.previous().clone(),
-                right: self.factor_rule(),
-            });
+        if let Some(precedence) = prefix_operator(&self.peek().kind) {
+            let operator = self.advance().clone();
+
+            return Ok(Box::new(Expr::UnaryExpression {
+                operator,
+                right: self.expression_at(precedence)?,
+            }));
         }
 
-        return expr;
+        return self.primary_rule();
     }
 
-    fn factor_rule(&mut self) -> Box<Expr> {
-        let mut expr = self.unary_rule();
+    /// Parses the operator at the current token, and what follows it, with `left` as its left
+    /// operand.
+    fn infix_rule(&mut self, left: Box<Expr>, operator: InfixOperator) -> Result<Box<Expr>, Error> {
+        // Parsing the right operand one level tighter keeps it from taking another operator of
+        // the same level, so that one applies to the whole expression instead.
+        let right_precedence = match operator.associativity {
+            Associativity::Left => operator.precedence.next(),
+            Associativity::Right => operator.precedence,
+        };
 
-        while self.tokens[self.current].kind == TokenKind::Slash
-            || self.tokens[self.current].kind == TokenKind::Star
-        {
-            self.advance();
+        return match operator.kind {
+            Infix::Assignment => {
+                let operator = self.advance().clone();
+                let value = self.expression_at(right_precedence)?;
+
+                if !self.check_assignable(&left, &operator) {
+                    return Ok(value);
+                }
+
+                if operator.kind != TokenKind::Equal {
+                    return Ok(Box::new(Expr::CompoundAssignmentExpression {
+                        target: left,
+                        operator,
+                        value,
+                        postfix: false,
+                    }));
+                }
+
+                // Assigning to a property turns the access into a set.
+                Ok(Box::new(match *left {
+                    Expr::GetExpression { object, name } => Expr::SetExpression {
+                        object,
+                        name,
+                        value,
+                    },
+                    Expr::VariableResolutionExpression { name, .. } => Expr::AssignmentExpression {
+                        name,
+                        value,
+                        binding: None,
+                    },
+                    _ => unreachable!("Checked to be assignable above."),
+                }))
+            }
+            Infix::Conditional => {
+                self.advance();
 
-            expr = Box::new(Expr::BinaryExpression {
-                left: expr,
-                operator: self.previous().clone(),
-                right: self.unary_rule(),
-            });
+                // The "?" and ":" delimit the then branch, so any expression fits in it.
+                let then_branch = self.expression_rule()?;
+
+                if self.peek().kind != TokenKind::Colon {
+                    return Err(self.error_at_current("Expected \":\" after the then branch of a conditional expression."));
+                }
+
+                self.advance();
+
+                Ok(Box::new(Expr::ConditionalExpression {
+                    condition: left,
+                    then_branch,
+                    else_branch: self.expression_at(right_precedence)?,
+                }))
+            }
+            Infix::Logical => {
+                let operator = self.advance().clone();
+
+                Ok(Box::new(Expr::LogicalExpression {
+                    left,
+                    operator,
+                    right: self.expression_at(right_precedence)?,
+                }))
+            }
+            Infix::Binary => {
+                let operator = self.advance().clone();
+
+                Ok(Box::new(Expr::BinaryExpression {
+                    left,
+                    operator,
+                    right: self.expression_at(right_precedence)?,
+                }))
+            }
+            Infix::Call => {
+                self.advance();
+
+                self.finish_call_rule(left)
+            }
+            Infix::Property => {
+                self.advance();
+
+                if self.peek().kind != TokenKind::Identifier {
+                    return Err(self.error("Expected property name after \".\"."));
+                }
+
+                Ok(Box::new(Expr::GetExpression {
+                    object: left,
+                    name: self.advance().clone(),
+                }))
+            }
+            Infix::Increment => {
+                let operator = self.advance().clone();
+
+                Ok(self.increment(left, operator, true))
+            }
+        };
+    }
+
+    /// Checks that `target` can be assigned to with `operator`. If it can't, records the error and
+    /// returns false. The parser isn't confused by this one, so it keeps going.
+    fn check_assignable(&mut self, target: &Expr, operator: &Token) -> bool {
+        if matches!(target, Expr::VariableResolutionExpression { .. } | Expr::GetExpression { .. }) {
+            return true;
+        }
+
+        self.errors.push(Error::new(
+            "Invalid assignment target.".into(),
+            Some(operator.line),
+            operator.column,
+            Some(operator.span),
+            Some("Only variables and properties can be assigned to.".into()),
+        ));
+
+        return false;
+    }
+
+    /// Turns `++` or `--` and its target into a compound assignment that adds or subtracts one. An
+    /// invalid target is reported and returned as it is.
+    fn increment(&mut self, target: Box<Expr>, operator: Token, postfix: bool) -> Box<Expr> {
+        if !self.check_assignable(&target, &operator) {
+            return target;
         }
 
-        return expr;
+        let one = Box::new(Expr::LiteralExpression {
+            value: Some(Literal::Number(1f64)),
+            span: operator.span,
+        });
+
+        return Box::new(Expr::CompoundAssignmentExpression {
+            target,
+            operator,
+            value: one,
+            postfix,
+        });
     }
 
-    fn unary_rule(&mut self) -> Box<Expr> {
-        if self.tokens[self.current].kind == TokenKind::Bang
-            || self.tokens[self.current].kind == TokenKind::Minus
-        {
-            self.advance();
+    /// Parses the arguments of a call. Expects the current token to be the one after "(".
+    fn finish_call_rule(&mut self, callee: Box<Expr>) -> Result<Box<Expr>, Error> {
+        let mut arguments = Vec::<Box<Expr>>::new();
 
-            let expr = Box::new(Expr::UnaryExpression {
-                operator: self.previous().clone(),
-                right: self.unary_rule(),
-            });
+        if self.peek().kind != TokenKind::RightParen {
+            loop {
+                if arguments.len() >= MAX_ARGUMENTS {
+                    // The parser isn't confused by this one, so it keeps going.
+                    let err = self.error(format!("Can't have more than {} arguments.", MAX_ARGUMENTS).as_str());
+                    self.errors.push(err);
+                }
+
+                arguments.push(self.expression_rule()?);
+
+                if self.peek().kind != TokenKind::Comma {
+                    break;
+                }
 
-            return expr;
+                self.advance();
+            }
         }
 
-        return self.primary_rule();
+        if self.peek().kind != TokenKind::RightParen {
+            return Err(self.error("Expected \")\" after arguments."));
+        }
+
+        self.advance();
+
+        return Ok(Box::new(Expr::CallExpression {
+            callee,
+            paren: self.previous().clone(),
+            arguments,
+        }));
     }
 
-    fn primary_rule(&mut self) -> Box<Expr> {
+    fn primary_rule(&mut self) -> Result<Box<Expr>, Error> {
         return if self.peek().kind == TokenKind::True {
             self.advance();
 
-            Box::new(Expr::LiteralExpression {
+            Ok(Box::new(Expr::LiteralExpression {
                 value: Some(Literal::Boolean(true)),
-            })
+                span: self.previous().span,
+            }))
         } else if self.peek().kind == TokenKind::False {
             self.advance();
 
-            Box::new(Expr::LiteralExpression {
+            Ok(Box::new(Expr::LiteralExpression {
                 value: Some(Literal::Boolean(false)),
-            })
+                span: self.previous().span,
+            }))
         } else if self.peek().kind == TokenKind::Nil {
             self.advance();
 
-            Box::new(Expr::LiteralExpression {
+            Ok(Box::new(Expr::LiteralExpression {
                 value: Some(Literal::Nil),
-            })
+                span: self.previous().span,
+            }))
         } else if self.peek().kind == TokenKind::String
             || self.peek().kind == TokenKind::Number
         {
             self.advance();
 
-            Box::new(Expr::LiteralExpression {
+            Ok(Box::new(Expr::LiteralExpression {
                 value: self.previous().literal.clone(),
-            })
-        } else if self.peek().kind == TokenKind::LeftParen {
-            // We don't capture any of the parentheses tokens. We only group the expression.
+                span: self.previous().span,
+            }))
+        } else if self.peek().kind == TokenKind::Interpolation {
+            self.interpolation_rule()
+        } else if self.peek().kind == TokenKind::Self_ {
+            self.advance();
+
+            Ok(Box::new(Expr::SelfExpression {
+                keyword: self.previous().clone(),
+                binding: None,
+            }))
+        } else if self.peek().kind == TokenKind::Super {
+            let keyword = self.advance().clone();
+
+            if self.peek().kind != TokenKind::Dot {
+                return Err(self.error("Expected \".\" after \"super\"."));
+            }
 
             self.advance();
 
-            let expr: Box<Expr> = self.expression_rule();
+            if self.peek().kind != TokenKind::Identifier {
+                return Err(self.error("Expected superclass method name."));
+            }
 
-            // Check if the next token is a closing parenthesis.
-            if self.peek().kind != TokenKind::RightParen {
-                let err = Error::new(
-                    "Expected \")\" after expression.".into(),
-                    Some(self.peek().line),
-                    self.peek().column,
-                    None,
-                );
+            Ok(Box::new(Expr::SuperExpression {
+                keyword,
+                method: self.advance().clone(),
+                binding: None,
+            }))
+        } else if self.peek().kind == TokenKind::LeftParen {
+            // We don't capture any of the parentheses tokens. We only group the expression, and
+            // remember where the parentheses were.
 
-                report_error(&err);
+            let start = self.advance().span;
 
-                self.errors.push(err);
+            let expr: Box<Expr> = self.expression_rule()?;
+
+            // Check if the next token is a closing parenthesis.
+            if self.peek().kind != TokenKind::RightParen {
+                return Err(self.error_at_current("Expected \")\" after expression."));
             }
 
-            self.advance();
+            let end = self.advance().span;
 
-            Box::new(Expr::GroupingExpression { expression: expr })
-        } else {
+            Ok(Box::new(Expr::GroupingExpression {
+                expression: expr,
+                span: start.to(end),
+            }))
+        } else if self.peek().kind == TokenKind::Identifier {
             self.advance();
 
-            Box::new(Expr::VariableResolutionExpression {
+            Ok(Box::new(Expr::VariableResolutionExpression {
                 name: self.previous().clone(),
-            })
+                binding: None,
+            }))
+        } else {
+            Err(self.error_at_current("Expected expression."))
         };
     }
 
-    /// Runs whenever we encounter a parsing error. It will discard the current statement and jump
-    /// to the next one.
-    fn synchronise(&mut self) {
-        while self.peek().kind != TokenKind::Semicolon {
-            self.advance();
+    /// Parses a string with embedded expressions. Expects the current token to be the text before
+    /// the first one.
+    fn interpolation_rule(&mut self) -> Result<Box<Expr>, Error> {
+        let mut parts = Vec::<Box<Expr>>::new();
+
+        while self.peek().kind == TokenKind::Interpolation {
+            let text = self.advance();
+
+            parts.push(Box::new(Expr::LiteralExpression {
+                value: text.literal.clone(),
+                span: text.span,
+            }));
+            parts.push(self.expression_rule()?);
         }
+
+        // The tokenizer ends the string with a `String` token once the last expression is closed.
+        if self.peek().kind != TokenKind::String {
+            return Err(self.error_at_current("Expected \"}\" after interpolated expression."));
+        }
+
+        let text = self.advance();
+
+        parts.push(Box::new(Expr::LiteralExpression {
+            value: text.literal.clone(),
+            span: text.span,
+        }));
+
+        return Ok(Box::new(Expr::InterpolationExpression { parts }));
+    }
+
+    /// Runs whenever we encounter a parsing error in the declaration that began at `start`.
+    /// Discards tokens until the start of the next statement: right after a ";", at a "}" closing
+    /// the enclosing block, or at a keyword that starts a statement. Stops at the end of the file.
+    ///
+    /// Braces opened by the broken declaration are skipped up to their matching "}", or that "}"
+    /// would be left behind and reported again as a stray token.
+    fn synchronise(&mut self, start: usize) {
+        let mut depth = 0usize;
+        for token in &self.tokens[start..self.current] {
+            match token.kind {
+                TokenKind::LeftBrace => depth += 1,
+                TokenKind::RightBrace => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+
+        while !self.is_at_end() {
+            match self.peek().kind {
+                TokenKind::LeftBrace => {
+                    depth += 1;
+                    self.advance();
+                }
+                TokenKind::RightBrace if depth > 0 => {
+                    depth -= 1;
+                    self.advance();
+
+                    if depth == 0 {
+                        return;
+                    }
+                }
+                _ if depth > 0 => {
+                    self.advance();
+                }
+                TokenKind::Semicolon => {
+                    self.advance();
+                    return;
+                }
+                TokenKind::RightBrace
+                | TokenKind::Class
+                | TokenKind::Fun
+                | TokenKind::Var
+                | TokenKind::For
+                | TokenKind::If
+                | TokenKind::While
+                | TokenKind::Print
+                | TokenKind::Return
+                | TokenKind::Break
+                | TokenKind::Continue => return,
+                _ => {
+                    self.advance();
+                }
+            }
+        }
+    }
+
+    /// Creates an error at the previous token.
+    fn error(&self, msg: &str) -> Error {
+        let token = self.previous();
+
+        return Error::new(msg.into(), Some(token.line), token.column, Some(token.span), None);
+    }
+
+    /// Creates an error at the given token, for when the one that's out of place was consumed earlier.
+    fn error_at(&self, token: &Token, msg: &str) -> Error {
+        return Error::new(msg.into(), Some(token.line), token.column, Some(token.span), None);
+    }
+
+    /// Creates an error at the current token, for when it's the one that's out of place.
+    fn error_at_current(&self, msg: &str) -> Error {
+        let token = self.peek();
+
+        return Error::new(msg.into(), Some(token.line), token.column, Some(token.span), None);
     }
 
-    /// Consumes a semicolon. If there is no semicolon, it will report an error.
-    fn consume_semicolon(&mut self) {
+    /// Consumes a semicolon. If there is no semicolon, it returns an error.
+    fn consume_semicolon(&mut self) -> Result<(), Error> {
         if self.peek().kind != TokenKind::Semicolon {
-            let err = Error::new(
+            // Points right after the previous token, where the semicolon is missing.
+            let previous = self.previous();
+
+            return Err(Error::new(
                 "Expected \";\" after expression.".into(),
-                // BUG: Line is currently incorrectly reported.
-                // Mayhaps we should think of when to advance the token and when to just peek.
-                Some(self.previous().line),
-                self.peek().column,
+                Some(previous.line),
+                previous.column + previous.lexeme.chars().count(),
+                Some(Span::new(previous.span.end, previous.span.end)),
                 None,
-            );
+            ));
+        }
 
-            report_error(&err);
+        self.advance();
 
-            self.errors.push(err);
-        } else {
-            self.advance();
-        }
+        return Ok(());
     }
 
     /// Get the next token and advance the current token.
@@ -536,39 +1151,17 @@ impl<'a> Parser<'a> {
 
     // Get the current token without advancing the current token.
     fn peek(&self) -> &Token {
-        return self.current_token();
+        return &self.tokens[self.current];
     }
 
-    /// Get the next token without advancing the current token.
+    /// Get the next token without advancing the current token. Past the end, that's the EOF token.
     fn peek_next(&self) -> &Token {
-        return self.tokens.get(self.current + 1).unwrap_or_else(|| {
-            panic!(
-                "Error peeking token. Current token is: {}, and is at index: {}",
-                self.peek(),
-                self.current - 1
-            );
-        });
-    }
-
-    fn current_token(&self) -> &Token {
-        return self.tokens.get(self.current).unwrap_or_else(|| {
-            panic!(
-                "Error getting current token. Previous token is: {}, and is at index: {}",
-                self.previous(),
-                self.current - 1
-            );
-        });
+        return self.tokens.get(self.current + 1).unwrap_or(self.peek());
     }
 
-    /// Get the previous token.
+    /// Get the previous token. At the start, that's the first token.
     fn previous(&self) -> &Token {
-        return self.tokens.get(self.current - 1).unwrap_or_else(|| {
-            panic!(
-                "Error getting previous token. Current token is: {}, and is at index: {}",
-                self.peek(),
-                self.current - 1
-            );
-        });
+        return &self.tokens[self.current.saturating_sub(1)];
     }
 }
 
@@ -588,6 +1181,10 @@ mod tests {
                 line: 1,
                 column: 0,
                 literal: None,
+                span: Span::default(),
+                doc: None,
+                leading_trivia: Vec::new(),
+                trailing_trivia: Vec::new(),
             },
             Token {
                 kind: TokenKind::Number,
@@ -595,6 +1192,10 @@ mod tests {
                 line: 1,
                 column: 1,
                 literal: Some(Literal::Number(123.into())),
+                span: Span::default(),
+                doc: None,
+                leading_trivia: Vec::new(),
+                trailing_trivia: Vec::new(),
             },
             Token {
                 kind: TokenKind::Star,
@@ -602,13 +1203,21 @@ mod tests {
                 line: 1,
                 column: 2,
                 literal: None,
+                span: Span::default(),
+                doc: None,
+                leading_trivia: Vec::new(),
+                trailing_trivia: Vec::new(),
             },
             Token {
                 kind: TokenKind::Number,
                 lexeme: "45.67".into(),
                 line: 1,
                 column: 3,
-                literal: Some(Literal::Number(45.67.into())),
+                literal: Some(Literal::Number(45.67)),
+                span: Span::default(),
+                doc: None,
+                leading_trivia: Vec::new(),
+                trailing_trivia: Vec::new(),
             },
             Token {
                 kind: TokenKind::RightParen,
@@ -616,6 +1225,10 @@ mod tests {
                 line: 1,
                 column: 0,
                 literal: None,
+                span: Span::default(),
+                doc: None,
+                leading_trivia: Vec::new(),
+                trailing_trivia: Vec::new(),
             },
             Token {
                 kind: TokenKind::Semicolon,
@@ -623,6 +1236,10 @@ mod tests {
                 line: 1,
                 column: 0,
                 literal: None,
+                span: Span::default(),
+                doc: None,
+                leading_trivia: Vec::new(),
+                trailing_trivia: Vec::new(),
             },
             Token {
                 kind: TokenKind::Eof,
@@ -630,20 +1247,28 @@ mod tests {
                 line: 1,
                 column: 4,
                 literal: None,
+                span: Span::default(),
+                doc: None,
+                leading_trivia: Vec::new(),
+                trailing_trivia: Vec::new(),
             },
         ];
 
         let mut parser = Parser::new(&tokens);
 
-        let statements = parser.parse();
+        let (statements, errors) = parser.parse();
+
+        assert!(errors.is_empty());
 
         assert_eq!(
             statements[0],
             Stmt::ExpressionStmt {
                 expression: Box::new(GroupingExpression {
+                    span: Span::default(),
                     expression: Box::new(Expr::BinaryExpression {
                         left: Box::new(Expr::LiteralExpression {
                             value: Some(Literal::Number(123.into())),
+                            span: Span::default(),
                         }),
                         operator: Token {
                             kind: TokenKind::Star,
@@ -651,13 +1276,603 @@ mod tests {
                             line: 1,
                             column: 2,
                             literal: None,
+                            span: Span::default(),
+                            doc: None,
+                            leading_trivia: Vec::new(),
+                            trailing_trivia: Vec::new(),
                         },
                         right: Box::new(Expr::LiteralExpression {
-                            value: Some(Literal::Number(45.67.into())),
+                            value: Some(Literal::Number(45.67)),
+                            span: Span::default(),
                         }),
                     }),
                 })
             }
         );
     }
+
+    #[test]
+    fn errors_are_returned_with_the_statements() {
+        let mut tokenizer = crate::tokenizer::Tokenizer::new("var a = 1;\nprint a");
+        let (tokens, _) = tokenizer.scan_tokens();
+
+        let mut parser = Parser::new(tokens);
+        let (statements, errors) = parser.parse();
+
+        // The statement with the error is discarded.
+        assert_eq!(statements.len(), 1);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].msg, "Expected \";\" after expression.");
+        assert_eq!((errors[0].line, errors[0].column), (Some(2), 8));
+    }
+
+    #[test]
+    fn function_declaration() {
+        let mut tokenizer = crate::tokenizer::Tokenizer::new("fun add(a, b) { return a + b; }");
+        let (tokens, _) = tokenizer.scan_tokens();
+
+        let mut parser = Parser::new(tokens);
+        let (statements, errors) = parser.parse();
+
+        assert_eq!(errors.len(), 0);
+        assert_eq!(statements.len(), 1);
+
+        if let Stmt::FunctionStmt { name, params, body, .. } = &statements[0] {
+            assert_eq!(name.lexeme, "add");
+            assert_eq!(params.len(), 2);
+            assert_eq!(body.len(), 1);
+            assert!(matches!(body[0], Stmt::ReturnStmt { .. }));
+        } else {
+            panic!("Expected a function statement, got {:?}", statements[0]);
+        }
+    }
+
+    #[test]
+    fn call_expression() {
+        let mut tokenizer = crate::tokenizer::Tokenizer::new("add(1, 2)(3);");
+        let (tokens, _) = tokenizer.scan_tokens();
+
+        let mut parser = Parser::new(tokens);
+        let (statements, errors) = parser.parse();
+
+        assert_eq!(errors.len(), 0);
+
+        if let Stmt::ExpressionStmt { expression } = &statements[0] {
+            if let Expr::CallExpression { callee, arguments, .. } = expression.as_ref() {
+                assert_eq!(arguments.len(), 1);
+                assert!(matches!(callee.as_ref(), Expr::CallExpression { .. }));
+            } else {
+                panic!("Expected a call expression, got {:?}", expression);
+            }
+        } else {
+            panic!("Expected an expression statement, got {:?}", statements[0]);
+        }
+    }
+
+    #[test]
+    fn class_declaration() {
+        let mut tokenizer = crate::tokenizer::Tokenizer::new("\
+        class B < A {
+            init(x) {
+                self.x = x;
+            }
+        }
+        ");
+        let (tokens, _) = tokenizer.scan_tokens();
+
+        let mut parser = Parser::new(tokens);
+        let (statements, errors) = parser.parse();
+
+        assert_eq!(errors.len(), 0);
+
+        if let Stmt::ClassStmt { name, methods, superclass, .. } = &statements[0] {
+            assert_eq!(name.lexeme, "B");
+            assert!(matches!(
+                superclass.as_deref(),
+                Some(Expr::VariableResolutionExpression { .. })
+            ));
+
+            if let Stmt::FunctionStmt { body, .. } = &methods[0] {
+                assert!(matches!(
+                    &body[0],
+                    Stmt::ExpressionStmt { expression } if matches!(expression.as_ref(), Expr::SetExpression { .. })
+                ));
+            } else {
+                panic!("Expected a method, got {:?}", methods[0]);
+            }
+        } else {
+            panic!("Expected a class statement, got {:?}", statements[0]);
+        }
+    }
+
+    #[test]
+    fn for_loop_desugars_into_while() {
+        let mut tokenizer =
+            crate::tokenizer::Tokenizer::new("for var i = 0; i < 10; i = i + 1 { print i; }");
+        let (tokens, _) = tokenizer.scan_tokens();
+
+        let mut parser = Parser::new(tokens);
+        let (statements, errors) = parser.parse();
+
+        assert_eq!(errors.len(), 0);
+
+        if let Stmt::BlockStmt { statements } = &statements[0] {
+            assert!(matches!(statements[0], Stmt::VarDeclStmt { .. }));
+            assert!(matches!(
+                &statements[1],
+                Stmt::WhileStmt { increment: Some(increment), .. } if matches!(increment.as_ref(), Stmt::AssignmentStmt { .. })
+            ));
+        } else {
+            panic!("Expected a block statement, got {:?}", statements[0]);
+        }
+    }
+
+    #[test]
+    fn condition_only_for_loop() {
+        let mut tokenizer = crate::tokenizer::Tokenizer::new("for a < 10 { a = a + 1; }");
+        let (tokens, _) = tokenizer.scan_tokens();
+
+        let mut parser = Parser::new(tokens);
+        let (statements, errors) = parser.parse();
+
+        assert_eq!(errors.len(), 0);
+        assert!(matches!(
+            statements[0],
+            Stmt::WhileStmt { increment: None, .. }
+        ));
+    }
+
+    #[test]
+    fn logical_operators_chain_to_the_left() {
+        let mut tokenizer = crate::tokenizer::Tokenizer::new("a or b or c and d and e;");
+        let (tokens, _) = tokenizer.scan_tokens();
+
+        let mut parser = Parser::new(tokens);
+        let (statements, errors) = parser.parse();
+
+        assert_eq!(errors.len(), 0);
+
+        // ((a or b) or ((c and d) and e))
+        let Stmt::ExpressionStmt { expression } = &statements[0] else {
+            panic!("Expected an expression statement.");
+        };
+        let Expr::LogicalExpression { left, right, .. } = expression.as_ref() else {
+            panic!("Expected a logical expression.");
+        };
+
+        assert!(matches!(
+            left.as_ref(),
+            Expr::LogicalExpression { operator: Token { kind: TokenKind::Or, .. }, .. }
+        ));
+        assert!(matches!(
+            right.as_ref(),
+            Expr::LogicalExpression {
+                operator: Token { kind: TokenKind::And, .. },
+                left,
+                ..
+            } if matches!(left.as_ref(), Expr::LogicalExpression { .. })
+        ));
+    }
+
+    mod break_and_continue {
+        use super::*;
+
+        fn parse_errors(source: &str) -> Vec<String> {
+            let mut tokenizer = crate::tokenizer::Tokenizer::new(source);
+            let (tokens, _) = tokenizer.scan_tokens();
+
+            let mut parser = Parser::new(tokens);
+            let (_, errors) = parser.parse();
+
+            return errors.into_iter().map(|err| err.msg).collect();
+        }
+
+        /// Parses the source and returns where its only error points.
+        fn error_position(source: &str) -> (Option<usize>, usize, Option<Span>) {
+            let mut tokenizer = crate::tokenizer::Tokenizer::new(source);
+            let (tokens, _) = tokenizer.scan_tokens();
+
+            let mut parser = Parser::new(tokens);
+            let (_, errors) = parser.parse();
+            assert_eq!(errors.len(), 1, "{:?}", errors);
+
+            return (errors[0].line, errors[0].column, errors[0].span);
+        }
+
+        #[test]
+        fn inside_loops() {
+            assert!(parse_errors("outer: while true { for ; ; { break outer; continue; } }").is_empty());
+        }
+
+        #[test]
+        fn outside_of_a_loop() {
+            assert_eq!(
+                parse_errors("break;"),
+                vec!["Can't use \"break\" outside of a loop."]
+            );
+            assert_eq!(error_position("break;"), (Some(1), 1, Some(Span::new(0, 5))));
+            assert_eq!(error_position("\n  continue label;"), (Some(2), 3, Some(Span::new(3, 11))));
+        }
+
+        #[test]
+        fn loops_dont_reach_into_functions() {
+            assert_eq!(
+                parse_errors("while true { fun f() { continue; } }"),
+                vec!["Can't use \"continue\" outside of a loop."]
+            );
+        }
+
+        #[test]
+        fn unknown_label() {
+            assert_eq!(
+                parse_errors("outer: while true { break inner; }"),
+                vec!["No enclosing loop is labeled \"inner\"."]
+            );
+            assert_eq!(
+                error_position("while true { break nope; }"),
+                (Some(1), 20, Some(Span::new(19, 23)))
+            );
+        }
+    }
+
+    mod if_statements {
+        use super::*;
+
+        #[test]
+        fn else_if_over_any_whitespace() {
+            let source = "\
+            if a {
+                print 1;
+            } else
+            if b {
+                print 2;
+            } else /* comment */ if c {
+                print 3;
+            } else {
+                print 4;
+            }
+            ";
+            let mut tokenizer = crate::tokenizer::Tokenizer::new(source);
+            let (tokens, _) = tokenizer.scan_tokens();
+
+            let mut parser = Parser::new(tokens);
+            let (statements, errors) = parser.parse();
+            assert!(errors.is_empty(), "{:?}", errors);
+
+            let Stmt::IfStmt {
+                else_if_branches,
+                else_branch,
+                ..
+            } = &statements[0]
+            else {
+                panic!("Expected an if statement.");
+            };
+            assert_eq!(else_if_branches.len(), 2);
+            assert!(else_branch.is_some());
+        }
+
+        #[test]
+        fn else_needs_a_block_or_if() {
+            let mut tokenizer = crate::tokenizer::Tokenizer::new("if a {} else print 1;");
+            let (tokens, _) = tokenizer.scan_tokens();
+
+            let mut parser = Parser::new(tokens);
+            let (_, errors) = parser.parse();
+
+            assert_eq!(errors[0].msg, "Expected \"{\" after \"else\".");
+        }
+    }
+
+    mod doc_comments {
+        use super::*;
+
+        #[test]
+        fn are_attached_to_declarations() {
+            let source = "\
+            /// The answer.
+            var answer = 42;
+
+            /// A greeter.
+            class Greeter {
+                /// Greets `name`.
+                greet(name) {
+                    /// The greeting.
+                    var greeting = \"Hi, \" + name;
+                }
+
+                bye() {}
+            }
+
+            /// Does nothing.
+            /// Twice.
+            fun noop() {}
+            ";
+            let mut tokenizer = crate::tokenizer::Tokenizer::new(source);
+            let (tokens, _) = tokenizer.scan_tokens();
+
+            let mut parser = Parser::new(tokens);
+            let (statements, errors) = parser.parse();
+            assert!(errors.is_empty(), "{:?}", errors);
+
+            let Stmt::VarDeclStmt { doc, .. } = &statements[0] else {
+                panic!("Expected a variable declaration.");
+            };
+            assert_eq!(doc.as_deref(), Some("The answer."));
+
+            let Stmt::ClassStmt { doc, methods, .. } = &statements[1] else {
+                panic!("Expected a class.");
+            };
+            assert_eq!(doc.as_deref(), Some("A greeter."));
+
+            let Stmt::FunctionStmt { doc, body, .. } = &methods[0] else {
+                panic!("Expected a method.");
+            };
+            assert_eq!(doc.as_deref(), Some("Greets `name`."));
+            assert!(matches!(&body[0], Stmt::VarDeclStmt { doc: Some(doc), .. } if doc == "The greeting."));
+            assert!(matches!(&methods[1], Stmt::FunctionStmt { doc: None, .. }));
+
+            let Stmt::FunctionStmt { doc, .. } = &statements[2] else {
+                panic!("Expected a function.");
+            };
+            assert_eq!(doc.as_deref(), Some("Does nothing.\nTwice."));
+        }
+    }
+
+    mod recovery {
+        use super::*;
+
+        fn parse(source: &str) -> (Vec<Stmt>, Vec<String>) {
+            let mut tokenizer = crate::tokenizer::Tokenizer::new(source);
+            let (tokens, _) = tokenizer.scan_tokens();
+
+            let mut parser = Parser::new(tokens);
+            let (statements, errors) = parser.parse();
+
+            return (statements, errors.into_iter().map(|err| err.msg).collect());
+        }
+
+        #[test]
+        fn collects_every_error() {
+            let (statements, errors) = parse("var = 1;\nprint (1;\nvar a = 2;\nprint * 2;\n");
+
+            assert_eq!(
+                errors,
+                vec![
+                    "Expected identifier after \"var\".",
+                    "Expected \")\" after expression.",
+                    "Expected expression.",
+                ]
+            );
+            assert_eq!(statements.len(), 1);
+        }
+
+        #[test]
+        fn stops_at_the_end_of_the_file() {
+            let (_, errors) = parse("print 1 +");
+            assert_eq!(errors, vec!["Expected expression."]);
+
+            let (_, errors) = parse("var a = 1");
+            assert_eq!(errors, vec!["Expected \";\" after expression."]);
+
+            let (_, errors) = parse("fun f() { print 1;");
+            assert_eq!(errors, vec!["Expected \"}\" after block."]);
+        }
+
+        #[test]
+        fn conditional_without_else() {
+            let (_, errors) = parse("var a = b ? c;");
+
+            assert_eq!(errors, vec!["Expected \":\" after the then branch of a conditional expression."]);
+        }
+
+        #[test]
+        fn invalid_assignment_targets() {
+            for source in ["a + b = c;", "(a) = 1;", "f() += 1;", "1 -= 2;", "a = b + c *= 2;", "1++;", "--f();", "++a++;"] {
+                let (statements, errors) = parse(source);
+
+                assert_eq!(errors, vec!["Invalid assignment target."], "{}", source);
+                assert_eq!(statements.len(), 1, "{}", source);
+            }
+        }
+
+        #[test]
+        fn resumes_at_a_keyword() {
+            let (statements, errors) = parse("print 1 2 3 var a = 1; print a;");
+
+            assert_eq!(errors, vec!["Expected \";\" after expression."]);
+            assert_eq!(statements.len(), 2);
+        }
+
+        #[test]
+        fn resumes_at_the_end_of_a_block() {
+            let (statements, errors) = parse("if true { print ) } print 2;");
+
+            assert_eq!(errors, vec!["Expected expression."]);
+            assert_eq!(statements.len(), 2);
+
+            if let Stmt::IfStmt { then_branch, .. } = &statements[0] {
+                assert_eq!(**then_branch, Stmt::BlockStmt { statements: vec![] });
+            } else {
+                panic!("Expected an if statement.");
+            }
+        }
+
+        #[test]
+        fn skips_the_braces_of_a_broken_statement() {
+            let (statements, errors) = parse("if x + { print 1; } print 2;");
+
+            assert_eq!(errors, vec!["Expected expression."]);
+            assert_eq!(statements.len(), 1);
+
+            let (statements, errors) = parse("class A { m( { } } print 2;");
+
+            assert_eq!(errors.len(), 1, "{:?}", errors);
+            assert_eq!(statements.len(), 1);
+
+            let (statements, errors) = parse("{ if x + { print 1; } print 2; } print 3;");
+
+            assert_eq!(errors, vec!["Expected expression."]);
+            assert_eq!(statements.len(), 2);
+        }
+
+        #[test]
+        fn unclosed_interpolated_expression() {
+            let (_, errors) = parse("print \"a ${b c}\"; print 1;");
+
+            assert_eq!(errors, vec!["Expected \"}\" after interpolated expression."]);
+        }
+
+        #[test]
+        fn skips_tokens_that_cant_start_a_statement() {
+            let (statements, errors) = parse(") ) print 1;");
+
+            assert_eq!(errors, vec!["Expected expression."]);
+            assert_eq!(statements.len(), 1);
+        }
+    }
+
+    mod precedence {
+        use super::*;
+
+        /// Parses an expression statement and writes its expression with every operation in
+        /// parentheses.
+        fn grouped(source: &str) -> String {
+            let mut tokenizer = crate::tokenizer::Tokenizer::new(source);
+            let (tokens, _) = tokenizer.scan_tokens();
+
+            let mut parser = Parser::new(tokens);
+            let (statements, errors) = parser.parse();
+            assert!(errors.is_empty(), "{:?}", errors);
+
+            return match &statements[0] {
+                Stmt::ExpressionStmt { expression } | Stmt::AssignmentStmt { expression } => {
+                    group(expression)
+                }
+                statement => panic!("Expected an expression statement, got {:?}.", statement),
+            };
+        }
+
+        fn group(expr: &Expr) -> String {
+            return match expr {
+                Expr::BinaryExpression { left, operator, right }
+                | Expr::LogicalExpression { left, operator, right } => {
+                    format!("({} {} {})", operator.lexeme, group(left), group(right))
+                }
+                Expr::UnaryExpression { operator, right } => {
+                    format!("({} {})", operator.lexeme, group(right))
+                }
+                Expr::AssignmentExpression { name, value, .. } => {
+                    format!("(= {} {})", name.lexeme, group(value))
+                }
+                Expr::CompoundAssignmentExpression { target, operator, postfix: true, .. } => {
+                    format!("({} {})", group(target), operator.lexeme)
+                }
+                Expr::CompoundAssignmentExpression { target, operator, value, .. } => {
+                    format!("({} {} {})", operator.lexeme, group(target), group(value))
+                }
+                Expr::ConditionalExpression { condition, then_branch, else_branch } => {
+                    format!("(?: {} {} {})", group(condition), group(then_branch), group(else_branch))
+                }
+                Expr::SetExpression { object, name, value } => {
+                    format!("(= (. {} {}) {})", group(object), name.lexeme, group(value))
+                }
+                Expr::GetExpression { object, name } => format!("(. {} {})", group(object), name.lexeme),
+                Expr::CallExpression { callee, arguments, .. } => {
+                    let mut ret = format!("(call {}", group(callee));
+                    for argument in arguments {
+                        ret += format!(" {}", group(argument)).as_str();
+                    }
+
+                    ret + ")"
+                }
+                Expr::GroupingExpression { expression, .. } => group(expression),
+                Expr::InterpolationExpression { parts } => {
+                    let parts: Vec<String> = parts.iter().map(|part| group(part)).collect();
+
+                    format!("(interpolate {})", parts.join(" "))
+                }
+                Expr::VariableResolutionExpression { name, .. } => name.lexeme.clone(),
+                Expr::LiteralExpression { value, .. } => value.clone().unwrap_or(Literal::Nil).to_string(),
+                expr => panic!("Unexpected expression {:?}.", expr),
+            };
+        }
+
+        #[test]
+        fn tighter_operators_take_the_operand() {
+            assert_eq!(grouped("1 + 2 * 3;"), "(+ 1 (* 2 3))");
+            assert_eq!(grouped("1 * 2 + 3;"), "(+ (* 1 2) 3)");
+            assert_eq!(grouped("a < b == c > d;"), "(== (< a b) (> c d))");
+            assert_eq!(grouped("a or b and c == d;"), "(or a (and b (== c d)))");
+            assert_eq!(grouped("-a * !b;"), "(* (- a) (! b))");
+        }
+
+        #[test]
+        fn remainder_and_integer_division_are_factors() {
+            assert_eq!(grouped("a + b % c;"), "(+ a (% b c))");
+            assert_eq!(grouped("a ~/ b * c % d;"), "(% (* (~/ a b) c) d)");
+        }
+
+        #[test]
+        fn exponent_is_right_associative_and_binds_tighter_than_unary() {
+            assert_eq!(grouped("a ** b ** c;"), "(** a (** b c))");
+            assert_eq!(grouped("-a ** b;"), "(- (** a b))");
+            assert_eq!(grouped("a ** -b;"), "(** a (- b))");
+            assert_eq!(grouped("a * b ** c.d;"), "(* a (** b (. c d)))");
+        }
+
+        #[test]
+        fn binary_operators_are_left_associative() {
+            assert_eq!(grouped("a - b - c;"), "(- (- a b) c)");
+            assert_eq!(grouped("a / b * c;"), "(* (/ a b) c)");
+        }
+
+        #[test]
+        fn assignment_is_right_associative() {
+            assert_eq!(grouped("a = b = c or d;"), "(= a (= b (or c d)))");
+            assert_eq!(grouped("a.b = c.d = 1;"), "(= (. a b) (= (. c d) 1))");
+        }
+
+        #[test]
+        fn postfix_forms_bind_tightest() {
+            assert_eq!(grouped("-a.b(c + d).e;"), "(- (. (call (. a b) (+ c d)) e))");
+            assert_eq!(grouped("f(1)(2);"), "(call (call f 1) 2)");
+        }
+
+        #[test]
+        fn interpolated_expressions() {
+            assert_eq!(
+                grouped("\"a ${b + c} d ${e}\";"),
+                "(interpolate a  (+ b c)  d  e )"
+            );
+        }
+
+        #[test]
+        fn compound_assignment() {
+            assert_eq!(grouped("a += b -= 1 + 2;"), "(+= a (-= b (+ 1 2)))");
+            assert_eq!(grouped("a.b *= c or d;"), "(*= (. a b) (or c d))");
+            assert_eq!(grouped("a.b.c /= 2;"), "(/= (. (. a b) c) 2)");
+            assert_eq!(grouped("a %= b = 3;"), "(%= a (= b 3))");
+        }
+
+        #[test]
+        fn increments() {
+            assert_eq!(grouped("a++;"), "(a ++)");
+            assert_eq!(grouped("-a.b--;"), "(- ((. a b) --))");
+            assert_eq!(grouped("++a.b;"), "(++ (. a b) 1)");
+            assert_eq!(grouped("a = --b * 2;"), "(= a (* (-- b 1) 2))");
+        }
+
+        #[test]
+        fn conditional_is_right_associative_and_below_or() {
+            assert_eq!(grouped("a ? b : c ? d : e;"), "(?: a b (?: c d e))");
+            assert_eq!(grouped("a or b ? c and d : e;"), "(?: (or a b) (and c d) e)");
+            assert_eq!(grouped("x = a ? b = 1 : c;"), "(= x (?: a (= b 1) c))");
+            assert_eq!(grouped("a ? b ? c : d : e;"), "(?: a (?: b c d) e)");
+        }
+
+        #[test]
+        fn grouping_overrides_precedence() {
+            assert_eq!(grouped("(1 + 2) * 3;"), "(* (+ 1 2) 3)");
+        }
+    }
 }
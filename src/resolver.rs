@@ -0,0 +1,555 @@
+use std::collections::HashMap;
+
+use crate::dialect::Dialect;
+use crate::expressions::{Binding, Expr};
+use crate::language_error::Error;
+use crate::stmt::Stmt;
+use crate::token::Token;
+
+/// The kind of function whose body is being resolved.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FunctionKind {
+    None,
+    Function,
+    Method,
+    Initializer,
+}
+
+/// The kind of class whose methods are being resolved.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ClassKind {
+    None,
+    Class,
+    Subclass,
+}
+
+/// A local variable declared in a scope.
+#[derive(Debug)]
+struct Variable {
+    /// The index of the variable in its scope at runtime.
+    slot: usize,
+    /// False while the variable's own initializer is being resolved.
+    defined: bool,
+}
+
+/// Resolver walks the AST before it runs and binds every use of a local variable to the scope and
+/// slot it lives in at runtime, so the interpreter doesn't have to look it up by name. Anything it
+/// can't find in a local scope is left unbound and looked up as a global.
+///
+/// Its scopes mirror the environments the interpreter creates: one per block, one per function
+/// call holding the parameters and the body's declarations, one holding `self` for methods, and one
+/// holding `super` for the methods of a subclass.
+///
+/// It also reports the errors that can be found without running the code.
+pub struct Resolver {
+    scopes: Vec<HashMap<String, Variable>>,
+    current_function: FunctionKind,
+    current_class: ClassKind,
+    dialect: Dialect,
+    errors: Vec<Error>,
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        return Resolver::new();
+    }
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        return Resolver::with_dialect(Dialect::default());
+    }
+
+    /// Creates a resolver that applies the scoping rules of the given dialect.
+    pub fn with_dialect(dialect: Dialect) -> Self {
+        return Resolver {
+            scopes: Vec::new(),
+            current_function: FunctionKind::None,
+            current_class: ClassKind::None,
+            dialect,
+            errors: Vec::new(),
+        };
+    }
+
+    /// Resolves the given top-level statements, storing the bindings it finds in them. Returns the
+    /// errors it found. The statements can't be run if there are any.
+    pub fn resolve(&mut self, statements: &mut [Stmt]) -> Vec<Error> {
+        self.resolve_statements(statements);
+
+        return std::mem::take(&mut self.errors);
+    }
+
+    fn resolve_statements(&mut self, statements: &mut [Stmt]) {
+        for statement in statements {
+            self.resolve_stmt(statement);
+        }
+    }
+
+    fn resolve_stmt(&mut self, stmt: &mut Stmt) {
+        match stmt {
+            Stmt::VarDeclStmt { name, initializer, .. } => {
+                self.declare(name, true);
+                self.resolve_expr(initializer);
+                self.define(name);
+            }
+            Stmt::AssignmentStmt { expression }
+            | Stmt::ExpressionStmt { expression }
+            | Stmt::PrintStmt { expression } => {
+                self.resolve_expr(expression);
+            }
+            Stmt::BlockStmt { statements } => {
+                self.begin_scope();
+                self.resolve_statements(statements);
+                self.end_scope();
+            }
+            Stmt::IfStmt {
+                condition,
+                then_branch,
+                else_if_branches,
+                else_branch,
+            } => {
+                self.resolve_expr(condition);
+                self.resolve_stmt(then_branch);
+
+                for else_if_branch in else_if_branches {
+                    self.resolve_stmt(else_if_branch);
+                }
+
+                if let Some(else_branch) = else_branch {
+                    self.resolve_stmt(else_branch);
+                }
+            }
+            Stmt::WhileStmt {
+                condition,
+                body,
+                increment,
+                ..
+            } => {
+                self.resolve_expr(condition);
+                self.resolve_stmt(body);
+
+                if let Some(increment) = increment {
+                    self.resolve_stmt(increment);
+                }
+            }
+            Stmt::BreakStmt { .. } | Stmt::ContinueStmt { .. } => (),
+            Stmt::FunctionStmt { name, params, body, .. } => {
+                // Declared before the body is resolved so the function can call itself.
+                self.declare(name, false);
+                self.define(name);
+
+                self.resolve_function(params, body, FunctionKind::Function);
+            }
+            Stmt::ReturnStmt { keyword, value } => {
+                if self.current_function == FunctionKind::None {
+                    self.error(keyword, "Cannot return from top-level code.");
+                }
+
+                if let Some(value) = value {
+                    if self.current_function == FunctionKind::Initializer {
+                        self.error(keyword, "Can't return a value from an initializer.");
+                    }
+
+                    self.resolve_expr(value);
+                }
+            }
+            Stmt::ClassStmt {
+                name,
+                methods,
+                superclass,
+                ..
+            } => {
+                let enclosing_class = self.current_class;
+                self.current_class = ClassKind::Class;
+
+                self.declare(name, false);
+                self.define(name);
+
+                if let Some(superclass) = superclass {
+                    if let Expr::VariableResolutionExpression { name: superclass_name, .. } = superclass.as_ref() {
+                        if superclass_name.lexeme == name.lexeme {
+                            self.error(superclass_name, "A class can't inherit from itself.");
+                        }
+                    }
+
+                    self.current_class = ClassKind::Subclass;
+                    self.resolve_expr(superclass);
+
+                    self.begin_scope();
+                    self.declare_builtin("super");
+                }
+
+                self.begin_scope();
+                self.declare_builtin("self");
+
+                for method in methods {
+                    if let Stmt::FunctionStmt { name, params, body, .. } = method {
+                        let kind = if name.lexeme == "init" {
+                            FunctionKind::Initializer
+                        } else {
+                            FunctionKind::Method
+                        };
+
+                        self.resolve_function(params, body, kind);
+                    }
+                }
+
+                self.end_scope();
+
+                if superclass.is_some() {
+                    self.end_scope();
+                }
+
+                self.current_class = enclosing_class;
+            }
+        }
+    }
+
+    /// Resolves a function's body. Its parameters and the declarations in its body share a scope,
+    /// the same way they share the environment of a call.
+    fn resolve_function(&mut self, params: &[Token], body: &mut [Stmt], kind: FunctionKind) {
+        let enclosing_function = self.current_function;
+        self.current_function = kind;
+
+        self.begin_scope();
+        for param in params {
+            self.declare(param, false);
+            self.define(param);
+        }
+        self.resolve_statements(body);
+        self.end_scope();
+
+        self.current_function = enclosing_function;
+    }
+
+    fn resolve_expr(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::AssignmentExpression {
+                name,
+                value,
+                binding,
+            } => {
+                self.resolve_expr(value);
+                *binding = self.resolve_local(&name.lexeme);
+            }
+            Expr::CompoundAssignmentExpression { target, value, .. } => {
+                self.resolve_expr(value);
+                self.resolve_expr(target);
+            }
+            Expr::BinaryExpression { left, right, .. } | Expr::LogicalExpression { left, right, .. } => {
+                self.resolve_expr(left);
+                self.resolve_expr(right);
+            }
+            Expr::CallExpression {
+                callee, arguments, ..
+            } => {
+                self.resolve_expr(callee);
+
+                for argument in arguments {
+                    self.resolve_expr(argument);
+                }
+            }
+            Expr::ConditionalExpression {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.resolve_expr(condition);
+                self.resolve_expr(then_branch);
+                self.resolve_expr(else_branch);
+            }
+            Expr::GetExpression { object, .. } => {
+                self.resolve_expr(object);
+            }
+            Expr::GroupingExpression { expression, .. } => {
+                self.resolve_expr(expression);
+            }
+            Expr::InterpolationExpression { parts } => {
+                for part in parts {
+                    self.resolve_expr(part);
+                }
+            }
+            Expr::LiteralExpression { .. } => (),
+            Expr::SetExpression { object, value, .. } => {
+                self.resolve_expr(value);
+                self.resolve_expr(object);
+            }
+            Expr::SuperExpression {
+                keyword, binding, ..
+            } => {
+                match self.current_class {
+                    ClassKind::None => self.error(keyword, "Can't use \"super\" outside of a class."),
+                    ClassKind::Class => {
+                        self.error(keyword, "Can't use \"super\" in a class with no superclass.")
+                    }
+                    ClassKind::Subclass => (),
+                }
+
+                *binding = self.resolve_local("super");
+            }
+            Expr::SelfExpression { keyword, binding } => {
+                if self.current_class == ClassKind::None {
+                    self.error(keyword, "Can't use \"self\" outside of a class.");
+                }
+
+                *binding = self.resolve_local("self");
+            }
+            Expr::UnaryExpression { right, .. } => {
+                self.resolve_expr(right);
+            }
+            Expr::VariableResolutionExpression { name, binding } => {
+                let in_own_initializer = self
+                    .scopes
+                    .last()
+                    .and_then(|scope| scope.get(&name.lexeme))
+                    .is_some_and(|variable| !variable.defined);
+
+                if in_own_initializer {
+                    self.error(name, "Can't read local variable in its own initializer.");
+                }
+
+                *binding = self.resolve_local(&name.lexeme);
+            }
+        }
+    }
+
+    /// Finds the innermost local scope declaring the given name. Returns `None` for globals.
+    fn resolve_local(&self, name: &str) -> Option<Binding> {
+        for (depth, scope) in self.scopes.iter().rev().enumerate() {
+            if let Some(variable) = scope.get(name) {
+                return Some(Binding {
+                    depth,
+                    slot: variable.slot,
+                });
+            }
+        }
+
+        return None;
+    }
+
+    fn begin_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    /// Adds the given name to the current local scope, without marking it as usable yet. In the
+    /// extended dialect, `var` declarations can't reuse a name from any enclosing local scope
+    /// either; globals are checked when the code runs.
+    fn declare(&mut self, name: &Token, is_var: bool) {
+        let already_declared = if is_var && self.dialect == Dialect::Extended {
+            self.scopes.iter().any(|scope| scope.contains_key(&name.lexeme))
+        } else {
+            self.scopes
+                .last()
+                .is_some_and(|scope| scope.contains_key(&name.lexeme))
+        };
+
+        if already_declared {
+            self.error(name, format!("Variable \"{}\" already declared.", name.lexeme).as_str());
+            return;
+        }
+
+        if let Some(scope) = self.scopes.last_mut() {
+            let slot = scope.len();
+            scope.insert(name.lexeme.clone(), Variable { slot, defined: false });
+        }
+    }
+
+    /// Marks the given name as usable in the current local scope.
+    fn define(&mut self, name: &Token) {
+        if let Some(variable) = self
+            .scopes
+            .last_mut()
+            .and_then(|scope| scope.get_mut(&name.lexeme))
+        {
+            variable.defined = true;
+        }
+    }
+
+    /// Adds a name the interpreter defines on its own, such as `self`, to the current scope.
+    fn declare_builtin(&mut self, name: &str) {
+        if let Some(scope) = self.scopes.last_mut() {
+            let slot = scope.len();
+            scope.insert(name.into(), Variable { slot, defined: true });
+        }
+    }
+
+    fn error(&mut self, token: &Token, msg: &str) {
+        self.errors.push(Error::new(
+            msg.into(),
+            Some(token.line),
+            token.column,
+            Some(token.span),
+            None,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::Parser;
+    use crate::tokenizer::Tokenizer;
+
+    use super::*;
+
+    /// Parses and resolves the given source code.
+    fn resolve(source: &str) -> (Vec<Stmt>, Vec<Error>) {
+        let mut tokenizer = Tokenizer::new(source);
+        let (tokens, _) = tokenizer.scan_tokens();
+
+        let (mut statements, _) = Parser::new(tokens).parse();
+
+        let errors = Resolver::new().resolve(&mut statements);
+
+        return (statements, errors);
+    }
+
+    fn error_messages(source: &str) -> Vec<String> {
+        let (_, errors) = resolve(source);
+
+        return errors.into_iter().map(|err| err.msg).collect();
+    }
+
+    mod bindings {
+        use super::*;
+
+        #[test]
+        fn globals_are_left_unbound() {
+            let (statements, errors) = resolve("var a = 1; print a;");
+
+            assert!(errors.is_empty());
+
+            let Stmt::PrintStmt { expression } = &statements[1] else {
+                panic!("Expected a print statement.");
+            };
+
+            assert!(matches!(
+                expression.as_ref(),
+                Expr::VariableResolutionExpression { binding: None, .. }
+            ));
+        }
+
+        #[test]
+        fn locals_get_their_depth_and_slot() {
+            let (statements, errors) = resolve("{ var a = 1; var b = 2; { print b; } }");
+
+            assert!(errors.is_empty());
+
+            let Stmt::BlockStmt { statements } = &statements[0] else {
+                panic!("Expected a block.");
+            };
+            let Stmt::BlockStmt { statements } = &statements[2] else {
+                panic!("Expected a nested block.");
+            };
+            let Stmt::PrintStmt { expression } = &statements[0] else {
+                panic!("Expected a print statement.");
+            };
+
+            assert!(matches!(
+                expression.as_ref(),
+                Expr::VariableResolutionExpression {
+                    binding: Some(Binding { depth: 1, slot: 1 }),
+                    ..
+                }
+            ));
+        }
+
+        #[test]
+        fn parameters_share_the_function_scope() {
+            let (statements, errors) = resolve("fun f(a, b) { var c = b; return c; }");
+
+            assert!(errors.is_empty());
+
+            let Stmt::FunctionStmt { body, .. } = &statements[0] else {
+                panic!("Expected a function.");
+            };
+            let Stmt::VarDeclStmt { initializer, .. } = &body[0] else {
+                panic!("Expected a variable declaration.");
+            };
+
+            assert!(matches!(
+                initializer.as_ref(),
+                Expr::VariableResolutionExpression {
+                    binding: Some(Binding { depth: 0, slot: 1 }),
+                    ..
+                }
+            ));
+        }
+    }
+
+    mod errors {
+        use super::*;
+
+        #[test]
+        fn reading_a_local_in_its_own_initializer() {
+            assert_eq!(
+                error_messages("{ var a = a; }"),
+                vec!["Can't read local variable in its own initializer."]
+            );
+        }
+
+        #[test]
+        fn return_outside_of_a_function() {
+            assert_eq!(
+                error_messages("return 1;"),
+                vec!["Cannot return from top-level code."]
+            );
+        }
+
+        #[test]
+        fn return_a_value_from_an_initializer() {
+            assert_eq!(
+                error_messages("class A { init() { return 1; } }"),
+                vec!["Can't return a value from an initializer."]
+            );
+        }
+
+        #[test]
+        fn self_outside_of_a_class() {
+            assert_eq!(
+                error_messages("fun f() { return self; }"),
+                vec!["Can't use \"self\" outside of a class."]
+            );
+        }
+
+        #[test]
+        fn super_without_a_superclass() {
+            assert_eq!(
+                error_messages("class A { f() { return super.f(); } }"),
+                vec!["Can't use \"super\" in a class with no superclass."]
+            );
+        }
+
+        #[test]
+        fn class_inheriting_from_itself() {
+            assert_eq!(
+                error_messages("class A < A {}"),
+                vec!["A class can't inherit from itself."]
+            );
+        }
+
+        #[test]
+        fn redeclaring_a_local() {
+            assert_eq!(
+                error_messages("{ var a = 1; { var a = 2; } }"),
+                vec!["Variable \"a\" already declared."]
+            );
+        }
+
+        #[test]
+        fn shadowing_in_the_reference_dialect() {
+            let mut tokenizer = Tokenizer::new("{ var a = 1; { var a = 2; } var b = 1; var b = 2; }");
+            let (tokens, _) = tokenizer.scan_tokens();
+
+            let (mut statements, _) = Parser::new(tokens).parse();
+
+            let errors = Resolver::with_dialect(Dialect::Reference).resolve(&mut statements);
+
+            let messages: Vec<String> = errors.into_iter().map(|err| err.msg).collect();
+            assert_eq!(messages, vec!["Variable \"b\" already declared."]);
+        }
+    }
+}
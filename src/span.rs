@@ -0,0 +1,21 @@
+/// Span is the part of the source code a token or an expression was read from, as the offsets of
+/// its first byte and of the byte right after its last one.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Span {
+        return Span { start, end };
+    }
+
+    /// Returns the span covering both this span and the given one, and everything between them.
+    pub fn to(self, other: Span) -> Span {
+        return Span {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        };
+    }
+}
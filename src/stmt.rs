@@ -1,7 +1,7 @@
 use crate::expressions::Expr;
 use crate::token::Token;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Stmt {
     /// Represents an expression wrapped in a statement.
     ExpressionStmt {
@@ -14,7 +14,9 @@ pub enum Stmt {
     /// Represents a variable declaration.
     VarDeclStmt {
         name: Token,
-        initializer: Expr,
+        initializer: Box<Expr>,
+        /// The doc comment written before the declaration.
+        doc: Option<String>,
     },
     /// Represents an assignment statement.
     AssignmentStmt {
@@ -29,14 +31,37 @@ pub enum Stmt {
         else_if_branches: Vec<Box<Stmt>>,
         else_branch: Option<Box<Stmt>>,
     },
+    /// Represents a `while` loop. `for` loops are desugared into it.
     WhileStmt {
         condition: Box<Expr>,
         body: Box<Stmt>,
+        /// Runs after every iteration of the body. Only set for `for` loops.
+        increment: Option<Box<Stmt>>,
+        /// Whether every iteration gets its own copy of the scope the loop runs in. Set for `for`
+        /// loops that declare a variable, so closures created in the body keep the value the
+        /// variable had in their iteration.
+        per_iteration_scope: bool,
+        /// The name given to the loop with `label: while ...`. `break` and `continue` can use it to
+        /// target an outer loop.
+        label: Option<Token>,
+    },
+    /// Represents a `break` statement. Without a label it exits the innermost loop.
+    BreakStmt {
+        keyword: Token,
+        label: Option<Token>,
+    },
+    /// Represents a `continue` statement. Without a label it skips to the next iteration of the
+    /// innermost loop.
+    ContinueStmt {
+        keyword: Token,
+        label: Option<Token>,
     },
     FunctionStmt {
         name: Token,
         params: Vec<Token>,
         body: Vec<Stmt>,
+        /// The doc comment written before the function or method.
+        doc: Option<String>,
     },
     ReturnStmt {
         keyword: Token,
@@ -46,8 +71,7 @@ pub enum Stmt {
         name: Token,
         methods: Vec<Stmt>,
         superclass: Option<Box<Expr>>,
+        /// The doc comment written before the class.
+        doc: Option<String>,
     },
-    None {
-        err: String,
-    } // This is for when there is an error and the parser cannot logically return a Stmt.
 }
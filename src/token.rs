@@ -1,7 +1,9 @@
 use std::fmt::Display;
 
 use crate::literal::Literal;
+use crate::span::Span;
 use crate::token_kinds::TokenKind;
+use crate::trivia::Trivia;
 
 /// Token is a struct that represents a token in a source file.
 #[derive(Debug, Clone, PartialEq)]
@@ -10,8 +12,17 @@ pub struct Token {
     /// The actual text of the token. Can be the variable name for identifiers.
     pub lexeme: String,
     pub line: usize,
+    /// The column of the token's first character in its line, starting from 1.
     pub column: usize,
     pub literal: Option<Literal>,
+    pub span: Span,
+    /// The text of the `///` doc comments right before the token, without the slashes.
+    pub doc: Option<String>,
+    /// The whitespace and comments before the token, after the previous token's trailing trivia.
+    /// Only kept by `Tokenizer::with_trivia`, like `trailing_trivia`.
+    pub leading_trivia: Vec<Trivia>,
+    /// The whitespace and comments after the token, up to and including the end of its line.
+    pub trailing_trivia: Vec<Trivia>,
 }
 
 impl Token {
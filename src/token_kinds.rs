@@ -13,6 +13,9 @@ pub enum TokenKind {
     Semicolon,
     Slash,
     Star,
+    Percent,
+    Colon,
+    Question,
 
     // One or two character tokens.
     Bang,
@@ -23,17 +26,30 @@ pub enum TokenKind {
     GreaterEqual,
     Less,
     LessEqual,
+    StarStar,
+    TildeSlash,
+    PlusEqual,
+    MinusEqual,
+    StarEqual,
+    SlashEqual,
+    PercentEqual,
+    PlusPlus,
+    MinusMinus,
 
     // Literals.
     Identifier,
     String,
+    /// The text of a string before a `${`. The tokens of the embedded expression follow it, then
+    /// the rest of the string as another `Interpolation` or a `String`.
+    Interpolation,
     Number,
 
     // Keywords.
     And,
+    Break,
     Class,
+    Continue,
     Else,
-    ElseIf,
     False,
     Fun,
     For,
@@ -48,6 +64,5 @@ pub enum TokenKind {
     Var,
     While,
 
-    Error,
     Eof,
 }
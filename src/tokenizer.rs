@@ -1,48 +1,132 @@
 use crate::language_error::Error;
 use crate::literal::Literal;
+use crate::span::Span;
 use crate::token::Token;
 use crate::token_kinds::TokenKind;
+use crate::trivia::{Trivia, TriviaKind};
 
 /// Tokenizer is responsible for scanning the source code and returning a vector of tokens and errors.
 /// The Tokenizer stores errors and returns them in a vector alongside the tokens.
+///
+/// Whitespace and comments are dropped, unless the Tokenizer is made with `with_trivia`. Then they
+/// are kept on the tokens as trivia, and the text of the source code is, in order, the leading
+/// trivia, lexeme and trailing trivia of every token.
 pub struct Tokenizer<'a> {
     source: &'a str,
     tokens: Vec<Token>,
+    /// The byte offset of the lexeme being scanned.
     start_of_lexeme: usize,
-    current_char: usize,
+    /// The byte offset of the next character to scan.
+    current: usize,
     line: usize,
+    /// The line the lexeme being scanned starts on. Only differs from `line` in multi-line strings.
+    line_of_lexeme: usize,
+    /// The column of the lexeme being scanned.
     column: usize,
+    /// The column of the next character to scan. Columns count characters, not bytes.
+    current_column: usize,
+    /// The lines of the doc comments read since the last token. They go to the next one.
+    doc: Option<String>,
+    /// Whether whitespace and comments are kept as trivia on the tokens.
+    keep_trivia: bool,
+    /// The trivia read since the last token.
+    trivia: Vec<Trivia>,
+    /// The interpolations in strings whose expression is being scanned, innermost last.
+    interpolations: Vec<Interpolation>,
     errors: Vec<Error>,
 }
 
+/// Interpolation is a `${` in a string whose embedded expression is being scanned.
+struct Interpolation {
+    /// Whether the string is between triple quotes, to know how it ends once the expression does.
+    multi_line: bool,
+    /// The number of braces open in the expression. The "}" closing the interpolation is the one
+    /// found when there are none.
+    open_braces: usize,
+    /// Where the `${` is, for when it's never closed.
+    line: usize,
+    column: usize,
+    span: Span,
+}
+
 impl<'a> Tokenizer<'a> {
-    pub fn new(source: &'a str) -> Tokenizer {
+    pub fn new(source: &'a str) -> Tokenizer<'a> {
         return Tokenizer {
             source,
             tokens: Vec::new(),
             start_of_lexeme: 0,
-            current_char: 0,
+            current: 0,
             line: 1,
+            line_of_lexeme: 1,
             column: 0,
+            current_column: 1,
+            doc: None,
+            keep_trivia: false,
+            trivia: Vec::new(),
+            interpolations: Vec::new(),
             errors: Vec::new(),
         };
     }
 
+    /// Makes a Tokenizer that only scans `source` from the byte `offset` on, which has to be the
+    /// start of a line. The spans and lines of the tokens are still the ones in all of `source`.
+    /// The REPL uses it to scan every input as part of the whole session.
+    pub fn starting_at(source: &'a str, offset: usize) -> Tokenizer<'a> {
+        let line = source[..offset].matches('\n').count() + 1;
+
+        return Tokenizer {
+            start_of_lexeme: offset,
+            current: offset,
+            line,
+            line_of_lexeme: line,
+            ..Tokenizer::new(source)
+        };
+    }
+
+    /// Makes a Tokenizer that keeps whitespace and comments as trivia on the tokens.
+    pub fn with_trivia(source: &'a str) -> Tokenizer<'a> {
+        return Tokenizer {
+            keep_trivia: true,
+            ..Tokenizer::new(source)
+        };
+    }
+
     /// scans the source code for tokens.
     pub fn scan_tokens(&mut self) -> (&Vec<Token>, &Vec<Error>) {
         while !self.is_at_end() {
-            self.start_of_lexeme = self.current_char;
-            self.column = self.start_of_lexeme + 1;
+            self.start_of_lexeme = self.current;
+            self.line_of_lexeme = self.line;
+            self.column = self.current_column;
 
+            let token_count = self.tokens.len();
             self.scan_token();
+
+            if self.keep_trivia && self.tokens.len() == token_count {
+                self.add_trivia();
+            }
+        }
+
+        for interpolation in std::mem::take(&mut self.interpolations) {
+            self.errors.push(Error::new(
+                "Unterminated interpolation.".into(),
+                Some(interpolation.line),
+                interpolation.column,
+                Some(interpolation.span),
+                Some("The expression in \"${...}\" must be followed by \"}\".".into()),
+            ));
         }
 
+        let leading_trivia = self.take_trivia();
         self.tokens.push(Token {
             kind: TokenKind::Eof,
             lexeme: "".into(),
             line: self.line,
-            column: self.column,
+            column: self.current_column,
             literal: None,
+            span: Span::new(self.current, self.current),
+            doc: self.doc.take(),
+            leading_trivia,
+            trailing_trivia: Vec::new(),
         });
 
         return (&self.tokens, &self.errors);
@@ -54,20 +138,81 @@ impl<'a> Tokenizer<'a> {
         let current_char = self.advance();
 
         match current_char {
-            '\n' => {
-                self.line += 1;
-            }
+            '\n' => self.new_line(),
+            '\r' if self.match_char('\n') => self.new_line(),
             ' ' | '\r' | '\t' => (),
             '(' => self.add_token(TokenKind::LeftParen, None),
             ')' => self.add_token(TokenKind::RightParen, None),
-            '{' => self.add_token(TokenKind::LeftBrace, None),
-            '}' => self.add_token(TokenKind::RightBrace, None),
+            '{' => {
+                if let Some(interpolation) = self.interpolations.last_mut() {
+                    interpolation.open_braces += 1;
+                }
+
+                self.add_token(TokenKind::LeftBrace, None);
+            }
+            '}' => match self.interpolations.last_mut() {
+                // The expression of the interpolation ends here, and the rest of its string starts.
+                Some(interpolation) if interpolation.open_braces == 0 => {
+                    let multi_line = interpolation.multi_line;
+                    self.interpolations.pop();
+
+                    self.string_part(multi_line);
+                }
+                Some(interpolation) => {
+                    interpolation.open_braces -= 1;
+
+                    self.add_token(TokenKind::RightBrace, None);
+                }
+                None => self.add_token(TokenKind::RightBrace, None),
+            },
             ',' => self.add_token(TokenKind::Comma, None),
             '.' => self.add_token(TokenKind::Dot, None),
-            '-' => self.add_token(TokenKind::Minus, None),
-            '+' => self.add_token(TokenKind::Plus, None),
+            '-' => {
+                let kind = if self.match_char('=') {
+                    TokenKind::MinusEqual
+                } else if self.match_char('-') {
+                    TokenKind::MinusMinus
+                } else {
+                    TokenKind::Minus
+                };
+
+                self.add_token(kind, None);
+            }
+            '+' => {
+                let kind = if self.match_char('=') {
+                    TokenKind::PlusEqual
+                } else if self.match_char('+') {
+                    TokenKind::PlusPlus
+                } else {
+                    TokenKind::Plus
+                };
+
+                self.add_token(kind, None);
+            }
             ';' => self.add_token(TokenKind::Semicolon, None),
-            '*' => self.add_token(TokenKind::Star, None),
+            '*' => {
+                let kind = if self.match_char('*') {
+                    TokenKind::StarStar
+                } else if self.match_char('=') {
+                    TokenKind::StarEqual
+                } else {
+                    TokenKind::Star
+                };
+
+                self.add_token(kind, None);
+            }
+            '%' => {
+                let kind = if self.match_char('=') {
+                    TokenKind::PercentEqual
+                } else {
+                    TokenKind::Percent
+                };
+
+                self.add_token(kind, None);
+            }
+            '~' if self.match_char('/') => self.add_token(TokenKind::TildeSlash, None),
+            ':' => self.add_token(TokenKind::Colon, None),
+            '?' => self.add_token(TokenKind::Question, None),
             '!' => {
                 // Check for the next character to see if it's a bang equal.
                 // If it is, add a bang equal token & increment `current` to skip it, otherwise
@@ -109,117 +254,347 @@ impl<'a> Tokenizer<'a> {
             }
             '/' => {
                 if self.match_char('/') {
-                    while self.peek() != '\n' && !self.is_at_end() {
+                    // Exactly three slashes start a doc comment. Four or more are a regular comment.
+                    let doc = self.peek() == '/' && self.peek_next_char() != '/';
+                    if doc {
+                        self.advance();
+                    }
+
+                    let start = self.current;
+                    while !self.at_end_of_line() {
                         self.advance();
                     }
+
+                    if doc {
+                        self.doc_comment_line(&self.source[start..self.current]);
+                    }
+                } else if self.match_char('*') {
+                    self.block_comment();
+                } else if self.match_char('=') {
+                    self.add_token(TokenKind::SlashEqual, None);
                 } else {
                     self.add_token(TokenKind::Slash, None);
                 }
             }
-            '"' => {
-                // As long as the next character isn't a double quote and we're not at the end
-                // of the source code, keep advancing.
-                while self.peek() != '"' && !self.is_at_end() && self.peek() != '\n' {
-                    self.advance();
-                }
+            '"' => self.string(),
+            _ => {
+                // Handle numbers and identifiers.
+                if current_char.is_ascii_digit() {
+                    self.number(current_char);
+                } else if current_char.is_ascii_alphabetic() || current_char == '_' {
+                    // Identifiers are letters, digits and underscores, and don't start with a digit.
+                    while self.peek().is_ascii_alphanumeric() || self.peek() == '_' {
+                        self.advance();
+                    }
+
+                    // Identify if the typed keyword is reserved or an identifier.
+                    let kind = self.match_keyword(&self.source[self.start_of_lexeme..self.current]);
+
+                    self.add_token(kind, None);
+                } else {
+                    let hint = match current_char {
+                        '~' => Some("Integer division is written \"~/\".".to_string()),
+                        _ => None,
+                    };
 
-                // If we're at the end of the source code before a closing '"', add an error.
-                if self.peek() == '\n' || self.is_at_end() {
                     self.errors.push(Error::new(
-                        "Unterminated string.".into(),
+                        format!("Unrecognized character \"{}\".", current_char),
                         Some(self.line),
                         self.column,
-                        None,
+                        Some(self.lexeme_span()),
+                        hint,
                     ));
+                }
+            }
+        }
+    }
+
+    /// doc_comment_line adds a line of a doc comment, the text after its `///`, to the doc comment
+    /// of the next token.
+    fn doc_comment_line(&mut self, text: &str) {
+        let text = text.strip_prefix(' ').unwrap_or(text);
+
+        match &mut self.doc {
+            Some(doc) => {
+                doc.push('\n');
+                doc.push_str(text);
+            }
+            None => self.doc = Some(text.into()),
+        }
+    }
+
+    /// block_comment skips a block comment whose opening `/*` was just consumed. Block comments
+    /// can be nested, and each `/*` needs its own `*/`.
+    fn block_comment(&mut self) {
+        let mut depth = 1;
+
+        while depth > 0 {
+            if self.is_at_end() {
+                self.errors.push(Error::new(
+                    "Unterminated block comment.".into(),
+                    Some(self.line_of_lexeme),
+                    self.column,
+                    Some(Span::new(self.start_of_lexeme, self.start_of_lexeme + 2)),
+                    Some("Every \"/*\" needs a matching \"*/\", including nested ones.".into()),
+                ));
+
+                return;
+            }
 
-                    return;
+            match self.advance() {
+                '/' if self.peek() == '*' => {
+                    self.advance();
+                    depth += 1;
+                }
+                '*' if self.peek() == '/' => {
+                    self.advance();
+                    depth -= 1;
                 }
+                '\n' => self.new_line(),
+                _ => (),
+            }
+        }
+    }
 
-                // Otherwise, we've found the closing '"', so we can add the string literal.
-                self.advance();
+    /// number scans a number literal whose first digit was just consumed. Besides decimals like
+    /// `6.02E23` and `1e-9`, it reads hexadecimal (`0xFF`), octal (`0o755`) and binary (`0b1010`)
+    /// integers. Digits can be separated by underscores, as in `1_000_000`.
+    fn number(&mut self, first_digit: char) {
+        let radix = match (first_digit, self.peek()) {
+            ('0', 'x' | 'X') => 16,
+            ('0', 'o' | 'O') => 8,
+            ('0', 'b' | 'B') => 2,
+            _ => 10,
+        };
+
+        if radix != 10 {
+            self.advance();
+        }
 
-                // The value of the string literal is the substring of the source code from the
-                // start index to the current index.
-                let value =
-                    self.source[self.start_of_lexeme + 1..self.current_char - 1].to_string();
-                self.add_token(TokenKind::String, Some(Literal::String(value)));
+        // Everything that can't end a number is read as part of it, so a malformed number is
+        // reported whole instead of being split into other tokens.
+        let mut previous = first_digit;
+        loop {
+            let char = self.peek();
+            let exponent_sign = radix == 10
+                && matches!(previous, 'e' | 'E')
+                && matches!(char, '+' | '-')
+                && self.peek_next_char().is_ascii_digit();
+            let fraction =
+                radix == 10 && char == '.' && self.peek_next_char().is_ascii_digit();
+
+            if !(char.is_alphanumeric() || char == '_' || exponent_sign || fraction) {
+                break;
             }
-            _ => {
-                // Handle numbers and identifiers.
-                if current_char.is_numeric() {
-                    // If it's a digit, scan and add a number token.
-                    while self.peek().is_numeric() {
-                        self.advance();
-                    }
 
-                    if self.peek() == '.' && self.peek_next_char().is_numeric() {
-                        self.advance();
+            previous = self.advance();
+        }
 
-                        while self.peek().is_numeric() {
-                            self.advance();
-                        }
-                    }
+        let text = &self.source[self.start_of_lexeme..self.current];
+        let value = if radix == 10 {
+            parse_decimal(text)
+        } else {
+            parse_integer(text, radix)
+        };
 
-                    let value = self.source[self.start_of_lexeme..self.current_char]
-                        .parse::<f64>()
-                        .unwrap_or_else(|err| {
-                            self.errors.push(Error::new(
-                                format!("Error parsing number: {}.", err),
-                                Some(self.line),
-                                self.column,
-                                None,
-                            ));
-
-                            return 0f64;
-                        });
-
-                    self.add_token(TokenKind::Number, Some(Literal::Number(value)));
-                } else if current_char.is_alphabetic() {
-                    // Identify if the typed keyword is reserved or an identifier.
+        let value = value.unwrap_or_else(|msg| {
+            self.errors.push(Error::new(
+                msg,
+                Some(self.line),
+                self.column,
+                Some(self.lexeme_span()),
+                None,
+            ));
 
-                    while !(self.peek() == '\n'
-                            || self.peek() == ' '
-                            || self.is_at_end()
-                            || self.peek() == '\t'
-                            || self.peek() == '(' // This is for function calls.
-                            || self.peek() == ')'
-                            || self.peek() == '{' // For names before scopes and blocks.
-                            || self.peek() == '}'
-                            || self.peek() == ','
-                            || self.peek() == '.'
-                            || self.peek() == ';')
-                    {
-                        self.advance();
-                    }
+            return 0f64;
+        });
+
+        self.add_token(TokenKind::Number, Some(Literal::Number(value)));
+    }
+
+    /// string scans a string literal whose opening quote was just consumed. Strings between
+    /// triple quotes can span multiple lines. Both kinds can contain escape sequences and
+    /// interpolations.
+    fn string(&mut self) {
+        let multi_line = self.peek() == '"' && self.peek_next_char() == '"';
+        if multi_line {
+            self.advance();
+            self.advance();
+        }
+
+        self.string_part(multi_line);
+    }
+
+    /// string_part scans the text of a string up to its closing quotes, or up to the next `${`.
+    /// The text before a `${` becomes an `Interpolation` token, followed by the tokens of the
+    /// embedded expression. The "}" after the expression resumes the string.
+    fn string_part(&mut self, multi_line: bool) {
+        let mut value = String::new();
+
+        loop {
+            if self.is_at_end() || (!multi_line && self.peek() == '\n') {
+                self.errors.push(Error::new(
+                    "Unterminated string.".into(),
+                    Some(self.line_of_lexeme),
+                    self.column,
+                    Some(self.lexeme_span()),
+                    None,
+                ));
+
+                return;
+            }
+
+            if self.peek() == '"' && (!multi_line || self.at_triple_quote()) {
+                break;
+            }
+
+            if self.peek() == '$' && self.peek_next_char() == '{' {
+                let (start, column) = (self.current, self.current_column);
+                self.advance();
+                self.advance();
 
-                    let value: &str = self.source[self.start_of_lexeme..self.current_char].into();
-
-                    let mut kind = self.match_keyword(value);
-
-                    // Continue matching for reserved words that consist of more than one word. E.g. `else if`.
-                    if kind == TokenKind::Else {
-                        if let Some(next_word) = self.peek_next_word() {
-                            if next_word == "if" {
-                                kind = TokenKind::ElseIf;
-                                // Advance the two next characters.
-                                self.advance();
-                                self.advance();
-                                self.advance();
-                            }
-                        }
+                self.interpolations.push(Interpolation {
+                    multi_line,
+                    open_braces: 0,
+                    line: self.line,
+                    column,
+                    span: Span::new(start, self.current),
+                });
+                self.add_token(TokenKind::Interpolation, Some(Literal::String(value)));
+
+                return;
+            }
+
+            match self.advance() {
+                '\\' => {
+                    if let Some(char) = self.escape_sequence() {
+                        value.push(char);
                     }
+                }
+                '\n' => {
+                    self.new_line();
 
-                    self.add_token(kind, None);
-                } else {
-                    self.errors.push(Error::new(
-                        format!("Unrecognized character \"{}\".", current_char),
-                        Some(self.line),
-                        self.column,
-                        None,
-                    ));
+                    value.push('\n');
                 }
+                char => value.push(char),
+            }
+        }
+
+        // Consume the closing quotes.
+        self.advance();
+        if multi_line {
+            self.advance();
+            self.advance();
+        }
+
+        self.add_token(TokenKind::String, Some(Literal::String(value)));
+    }
+
+    /// at_triple_quote checks if the Tokenizer is at the `"""` closing a multi-line string.
+    fn at_triple_quote(&self) -> bool {
+        return self.source[self.current..].starts_with("\"\"\"");
+    }
+
+    /// escape_sequence scans the escape sequence after a backslash in a string and returns the
+    /// character it stands for. If it isn't a valid escape sequence, it adds an error and returns
+    /// None.
+    fn escape_sequence(&mut self) -> Option<char> {
+        // The backslash, which is one byte and one column wide.
+        let (start, column) = (self.current - 1, self.current_column - 1);
+
+        // The string ends right after the backslash. That's reported once the string ends.
+        if self.is_at_end() || self.peek() == '\n' {
+            self.escape_error("Expected an escape sequence after \"\\\".".into(), start, column, None);
+
+            return None;
+        }
+
+        return match self.advance() {
+            'n' => Some('\n'),
+            't' => Some('\t'),
+            'r' => Some('\r'),
+            '0' => Some('\0'),
+            '\\' => Some('\\'),
+            '"' => Some('"'),
+            '$' => Some('$'),
+            'u' => self.unicode_escape(start, column),
+            char => {
+                self.escape_error(
+                    format!("Unknown escape sequence \"\\{}\".", char),
+                    start,
+                    column,
+                    Some("The escape sequences are \\n, \\t, \\r, \\0, \\\\, \\\", \\$ and \\u{...}.".into()),
+                );
+
+                None
             }
+        };
+    }
+
+    /// unicode_escape scans the `{...}` of a `\u{...}` escape sequence, which holds the code point
+    /// of a character in 1 to 6 hexadecimal digits. `start` and `column` are where the backslash is.
+    fn unicode_escape(&mut self, start: usize, column: usize) -> Option<char> {
+        if self.peek() != '{' {
+            self.escape_error("Expected \"{\" after \"\\u\".".into(), start, column, None);
+
+            return None;
+        }
+
+        self.advance();
+
+        let mut digits = String::new();
+        while self.peek().is_ascii_hexdigit() {
+            digits.push(self.advance());
+        }
+
+        if self.peek() != '}' {
+            self.escape_error(
+                "Expected hexadecimal digits and \"}\" in \"\\u{...}\".".into(),
+                start,
+                column,
+                None,
+            );
+
+            return None;
+        }
+
+        self.advance();
+
+        if digits.is_empty() || digits.len() > 6 {
+            self.escape_error(
+                "A Unicode escape sequence must have 1 to 6 hexadecimal digits.".into(),
+                start,
+                column,
+                None,
+            );
+
+            return None;
         }
+
+        let code_point = u32::from_str_radix(&digits, 16).unwrap();
+        let char = char::from_u32(code_point);
+
+        if char.is_none() {
+            self.escape_error(
+                format!("\"\\u{{{}}}\" isn't a Unicode character.", digits),
+                start,
+                column,
+                Some("Surrogates and code points above 10FFFF can't be written in a string.".into()),
+            );
+        }
+
+        return char;
+    }
+
+    /// escape_error adds an error about the escape sequence from `start` to the current character.
+    fn escape_error(&mut self, msg: String, start: usize, column: usize, hint: Option<String>) {
+        self.errors.push(Error::new(
+            msg,
+            Some(self.line),
+            column,
+            Some(Span::new(start, self.current)),
+            hint,
+        ));
     }
 
     /// match_keyword checks if the given word is a keyword.
@@ -227,7 +602,9 @@ impl<'a> Tokenizer<'a> {
     fn match_keyword(&self, word: &str) -> TokenKind {
         match word {
             "and" => TokenKind::And,
+            "break" => TokenKind::Break,
             "class" => TokenKind::Class,
+            "continue" => TokenKind::Continue,
             "else" => TokenKind::Else,
             "false" => TokenKind::False,
             "for" => TokenKind::For,
@@ -253,103 +630,207 @@ impl<'a> Tokenizer<'a> {
     fn add_token(&mut self, kind: TokenKind, literal: Option<Literal>) {
         // The text of the token is the substring of the source code from the start index to the
         // current index.
-        let text = &self.source[self.start_of_lexeme..self.current_char];
+        let text = &self.source[self.start_of_lexeme..self.current];
+        let leading_trivia = self.take_trivia();
         self.tokens.push(Token {
             kind,
             lexeme: text.to_string(),
-            line: self.line,
+            line: self.line_of_lexeme,
             column: self.column,
             literal,
+            span: self.lexeme_span(),
+            doc: self.doc.take(),
+            leading_trivia,
+            trailing_trivia: Vec::new(),
         });
     }
 
+    /// add_trivia keeps the lexeme that was just scanned as trivia, since it didn't make a token.
+    fn add_trivia(&mut self) {
+        let text = &self.source[self.start_of_lexeme..self.current];
+        if text.is_empty() {
+            return;
+        }
+
+        let kind = if text.starts_with("///") && !text.starts_with("////") {
+            TriviaKind::DocComment
+        } else if text.starts_with("//") {
+            TriviaKind::LineComment
+        } else if text.starts_with("/*") {
+            TriviaKind::BlockComment
+        } else if text.ends_with('\n') {
+            TriviaKind::Newline
+        } else if text.chars().all(char::is_whitespace) {
+            TriviaKind::Whitespace
+        } else {
+            TriviaKind::Skipped
+        };
+
+        // Consecutive spaces and tabs are a single piece of whitespace.
+        if let Some(last) = self.trivia.last_mut() {
+            if kind == TriviaKind::Whitespace && last.kind == TriviaKind::Whitespace {
+                last.text.push_str(text);
+                last.span.end = self.current;
+
+                return;
+            }
+        }
+
+        self.trivia.push(Trivia {
+            kind,
+            text: text.into(),
+            span: self.lexeme_span(),
+        });
+    }
+
+    /// take_trivia hands the trivia read since the last token to the tokens around it. The trivia
+    /// up to the end of the last token's line is its trailing trivia. The rest is returned, as the
+    /// leading trivia of the token being added.
+    fn take_trivia(&mut self) -> Vec<Trivia> {
+        let mut trivia = std::mem::take(&mut self.trivia);
+
+        if let Some(last_token) = self.tokens.last_mut() {
+            let end_of_line = trivia
+                .iter()
+                .position(|piece| piece.kind == TriviaKind::Newline)
+                .map_or(trivia.len(), |index| index + 1);
+
+            last_token.trailing_trivia = trivia.drain(..end_of_line).collect();
+        }
+
+        return trivia;
+    }
+
+    /// lexeme_span returns the span of the lexeme being scanned.
+    fn lexeme_span(&self) -> Span {
+        return Span::new(self.start_of_lexeme, self.current);
+    }
+
     /// match_char checks if the next character is the expected character.
-    /// If it is, it increments the current index and returns true, otherwise it returns false.
+    /// If it is, it consumes it and returns true, otherwise it returns false.
     /// This is useful for checking for multi-character tokens like `!=` or `==`.
     fn match_char(&mut self, expected_next: char) -> bool {
-        if self.is_at_end() {
-            return false;
-        }
-
-        let next_char = self
-            .source
-            .chars()
-            .nth(self.current_char)
-            .unwrap_or_else(|| {
-                panic!(
-                    "No character at index {}. Last read character was {}.",
-                    self.current_char,
-                    self.source.chars().nth(self.current_char - 1).unwrap()
-                );
-            });
-        if next_char != expected_next {
+        if self.is_at_end() || self.peek() != expected_next {
             return false;
         }
 
-        self.current_char += 1;
+        self.advance();
         return true;
     }
 
     /// advance consumes the current character the Tokenizer's at and returns it.
-    /// Then it increments the current index.
+    /// At the end of the source code, it returns the null character and stays there.
     fn advance(&mut self) -> char {
-        let char = self
-            .source
-            .chars()
-            .nth(self.current_char)
-            .unwrap_or_else(|| {
-                println!(
-                    "No character at index {}. Last read character was {}.",
-                    self.current_char,
-                    self.source.chars().nth(self.current_char - 1).unwrap()
-                );
-                std::process::exit(1);
-            });
-        self.current_char += 1;
+        let char = self.peek();
+
+        if !self.is_at_end() {
+            self.current += char.len_utf8();
+            self.current_column += 1;
+        }
 
         return char;
     }
 
+    /// new_line moves the Tokenizer's position to the next line, after consuming a newline.
+    fn new_line(&mut self) {
+        self.line += 1;
+        self.current_column = 1;
+    }
+
     /// peek returns the current character the Tokenizer's at without consuming it.
     /// If the Tokenizer is at the end of the source code, it returns the null character.
     fn peek(&self) -> char {
-        if self.is_at_end() {
-            return '\0';
-        }
-
-        return self.source.chars().nth(self.current_char).unwrap();
+        return self.source[self.current..].chars().next().unwrap_or('\0');
     }
 
     /// peek_next returns the next character the Tokenizer's at without consuming it.
     fn peek_next_char(&self) -> char {
-        if self.current_char + 1 >= self.source.len() {
-            return '\0';
-        }
+        let mut chars = self.source[self.current..].chars();
+        chars.next();
 
-        return self.source.chars().nth(self.current_char + 1).unwrap();
+        return chars.next().unwrap_or('\0');
     }
 
-    /// peek_next_word returns the next word without consuming it.
-    fn peek_next_word(&self) -> Option<String> {
-        let mut ret = String::new();
-        let mut current = self.current_char + 1;
-        if !self.is_at_end() {
-            while self.source.chars().nth(current)? != ' ' {
-                ret.push(self.source.chars().nth(current).unwrap());
+    /// Checks if the Tokenizer is at a line break, `\n` or `\r\n`, or at the end of the source code.
+    fn at_end_of_line(&self) -> bool {
+        return self.is_at_end()
+            || self.peek() == '\n'
+            || (self.peek() == '\r' && self.peek_next_char() == '\n');
+    }
 
-                current += 1;
-            }
+    /// Checks if the Tokenizer is at the end of the source code.
+    fn is_at_end(&self) -> bool {
+        return self.current >= self.source.len();
+    }
+}
 
-            return Some(ret);
+/// parse_decimal parses the text of a decimal number literal: digits, optionally followed by a
+/// fraction and an exponent. Returns the error message if it's malformed.
+fn parse_decimal(text: &str) -> Result<f64, String> {
+    let (mantissa, exponent) = match text.find(['e', 'E']) {
+        Some(index) => (&text[..index], Some(&text[index + 1..])),
+        None => (text, None),
+    };
+    let (integer, fraction) = match mantissa.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (mantissa, None),
+    };
+
+    check_digits(integer, 10)?;
+    if let Some(fraction) = fraction {
+        check_digits(fraction, 10)?;
+    }
+    if let Some(exponent) = exponent {
+        let digits = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+        if digits.is_empty() {
+            return Err(format!("Expected digits in the exponent of \"{}\".", text));
         }
 
-        return None;
+        check_digits(digits, 10)?;
     }
 
-    /// Checks if the Tokenizer is at the end of the source code.
-    fn is_at_end(&self) -> bool {
-        return self.current_char >= self.source.len();
+    // What's left after the checks is always a valid float.
+    return Ok(text.replace('_', "").parse::<f64>().unwrap());
+}
+
+/// parse_integer parses the text of an integer literal in the given radix, which starts with the
+/// radix's prefix. Returns the error message if it's malformed.
+fn parse_integer(text: &str, radix: u32) -> Result<f64, String> {
+    let (prefix, digits) = text.split_at(2);
+    if digits.is_empty() {
+        return Err(format!("Expected digits after \"{}\".", prefix));
+    }
+
+    check_digits(digits, radix)?;
+
+    // Accumulating into a float keeps integers too big for a u64 from overflowing. They lose
+    // precision, like every integer past 2^53 does.
+    return Ok(digits
+        .chars()
+        .filter_map(|char| char.to_digit(radix))
+        .fold(0f64, |value, digit| value * radix as f64 + digit as f64));
+}
+
+/// check_digits checks that `digits` only has digits of the radix, with underscores between them.
+fn check_digits(digits: &str, radix: u32) -> Result<(), String> {
+    if let Some(char) = digits.chars().find(|char| *char != '_' && !char.is_digit(radix)) {
+        return Err(format!("Invalid digit \"{}\" in {} number.", char, radix_name(radix)));
+    }
+
+    if digits.is_empty() || digits.starts_with('_') || digits.ends_with('_') || digits.contains("__") {
+        return Err("Underscores in numbers can only separate digits.".into());
     }
+
+    return Ok(());
+}
+
+fn radix_name(radix: u32) -> &'static str {
+    return match radix {
+        16 => "hexadecimal",
+        8 => "octal",
+        2 => "binary",
+        _ => "decimal",
+    };
 }
 
 #[cfg(test)]
@@ -380,6 +861,124 @@ mod tests {
             assert_eq!(errors.len(), 0);
         }
 
+        #[test]
+        fn arithmetic_operators() {
+            let mut tokenizer = Tokenizer::new("* ** % ~/ / ***");
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+            let kinds: Vec<TokenKind> = tokens.iter().map(|token| token.kind.clone()).collect();
+
+            assert!(errors.is_empty(), "{:?}", errors);
+            assert_eq!(
+                kinds,
+                vec![
+                    TokenKind::Star,
+                    TokenKind::StarStar,
+                    TokenKind::Percent,
+                    TokenKind::TildeSlash,
+                    TokenKind::Slash,
+                    TokenKind::StarStar,
+                    TokenKind::Star,
+                    TokenKind::Eof,
+                ]
+            );
+        }
+
+        #[test]
+        fn compound_assignment_operators() {
+            let mut tokenizer = Tokenizer::new("+= -= *= /= %= + = /* /= */ // /=");
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+            let kinds: Vec<TokenKind> = tokens.iter().map(|token| token.kind.clone()).collect();
+
+            assert!(errors.is_empty(), "{:?}", errors);
+            assert_eq!(
+                kinds,
+                vec![
+                    TokenKind::PlusEqual,
+                    TokenKind::MinusEqual,
+                    TokenKind::StarEqual,
+                    TokenKind::SlashEqual,
+                    TokenKind::PercentEqual,
+                    TokenKind::Plus,
+                    TokenKind::Equal,
+                    TokenKind::Eof,
+                ]
+            );
+        }
+
+        #[test]
+        fn increment_operators() {
+            let mut tokenizer = Tokenizer::new("a++ --b - -c +++");
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+            let kinds: Vec<TokenKind> = tokens.iter().map(|token| token.kind.clone()).collect();
+
+            assert!(errors.is_empty(), "{:?}", errors);
+            assert_eq!(
+                kinds,
+                vec![
+                    TokenKind::Identifier,
+                    TokenKind::PlusPlus,
+                    TokenKind::MinusMinus,
+                    TokenKind::Identifier,
+                    TokenKind::Minus,
+                    TokenKind::Minus,
+                    TokenKind::Identifier,
+                    TokenKind::PlusPlus,
+                    TokenKind::Plus,
+                    TokenKind::Eof,
+                ]
+            );
+        }
+
+        #[test]
+        fn conditional_operator() {
+            let mut tokenizer = Tokenizer::new("a ? b : c");
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+            let kinds: Vec<TokenKind> = tokens.iter().map(|token| token.kind.clone()).collect();
+
+            assert!(errors.is_empty(), "{:?}", errors);
+            assert_eq!(
+                kinds,
+                vec![
+                    TokenKind::Identifier,
+                    TokenKind::Question,
+                    TokenKind::Identifier,
+                    TokenKind::Colon,
+                    TokenKind::Identifier,
+                    TokenKind::Eof,
+                ]
+            );
+        }
+
+        #[test]
+        fn lone_tilde() {
+            let mut tokenizer = Tokenizer::new("a ~ b");
+
+            let (_, errors) = tokenizer.scan_tokens();
+
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].msg, "Unrecognized character \"~\".");
+            assert_eq!(errors[0].hint, Some("Integer division is written \"~/\".".into()));
+        }
+
+        #[test]
+        fn starting_at_an_offset() {
+            let source = "var a = 1;\nprint a;\n";
+            let mut tokenizer = Tokenizer::starting_at(source, 11);
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+
+            assert!(errors.is_empty(), "{:?}", errors);
+            assert_eq!(tokens.len(), 4);
+            assert_eq!(tokens[0].kind, TokenKind::Print);
+            assert_eq!((tokens[0].line, tokens[0].column), (2, 1));
+            assert_eq!(tokens[1].span, Span::new(17, 18));
+            assert_eq!(tokens[3].line, 3);
+        }
+
         #[test]
         fn comments() {
             let input = "() // This is a comment )";
@@ -392,7 +991,7 @@ mod tests {
 
         #[test]
         fn whitespace() {
-            let input = " ( ) ".into();
+            let input = " ( ) ";
             let mut tokenizer = Tokenizer::new(input);
 
             let (tokens, errors) = tokenizer.scan_tokens();
@@ -401,22 +1000,41 @@ mod tests {
         }
 
         #[test]
-        fn strings() {
-            let input = "\"Hello, world!\"".into();
+        fn columns_and_spans() {
+            let input = "var a;\n  print a;";
             let mut tokenizer = Tokenizer::new(input);
 
             let (tokens, errors) = tokenizer.scan_tokens();
-            assert_eq!(tokens.len(), 2);
             assert_eq!(errors.len(), 0);
+
+            let print = &tokens[3];
+            assert_eq!(print.lexeme, "print");
+            assert_eq!((print.line, print.column), (2, 3));
+            assert_eq!(print.span, Span::new(9, 14));
+            assert_eq!(&input[print.span.start..print.span.end], "print");
+
+            let a = &tokens[4];
+            assert_eq!((a.line, a.column), (2, 9));
+            assert_eq!(a.span, Span::new(15, 16));
         }
 
         #[test]
-        fn unterminated_string() {
-            let input = "\"Hello, world!))";
+        fn strings() {
+            let input = "\"Hello, world!\"";
             let mut tokenizer = Tokenizer::new(input);
 
             let (tokens, errors) = tokenizer.scan_tokens();
-
+            assert_eq!(tokens.len(), 2);
+            assert_eq!(errors.len(), 0);
+        }
+
+        #[test]
+        fn unterminated_string() {
+            let input = "\"Hello, world!))";
+            let mut tokenizer = Tokenizer::new(input);
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+
             assert_eq!(tokens.len(), 1);
             assert_eq!(errors.len(), 1);
             assert_eq!(errors[0].line, Some(1));
@@ -435,6 +1053,647 @@ mod tests {
         }
     }
 
+    mod comments {
+        use super::*;
+
+        #[test]
+        fn block_comments_span_lines() {
+            let input = "a /* one\ntwo */ b\n/**/c";
+            let mut tokenizer = Tokenizer::new(input);
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+            assert!(errors.is_empty(), "{:?}", errors);
+
+            let found: Vec<(&str, usize, usize)> = tokens
+                .iter()
+                .map(|token| (token.lexeme.as_str(), token.line, token.column))
+                .collect();
+            assert_eq!(found, vec![("a", 1, 1), ("b", 2, 8), ("c", 3, 5), ("", 3, 6)]);
+        }
+
+        #[test]
+        fn block_comments_nest() {
+            let input = "a /* outer /* inner */ still a comment */ b";
+            let mut tokenizer = Tokenizer::new(input);
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+            let lexemes: Vec<&str> = tokens.iter().map(|token| token.lexeme.as_str()).collect();
+
+            assert!(errors.is_empty(), "{:?}", errors);
+            assert_eq!(lexemes, vec!["a", "b", ""]);
+        }
+
+        #[test]
+        fn unterminated_block_comment() {
+            let input = "a;\n  /* /* */\nb;";
+            let mut tokenizer = Tokenizer::new(input);
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+
+            assert_eq!(tokens.len(), 3);
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].msg, "Unterminated block comment.");
+            assert_eq!((errors[0].line, errors[0].column), (Some(2), 3));
+            assert_eq!(errors[0].span, Some(Span::new(5, 7)));
+        }
+
+        #[test]
+        fn doc_comments_go_to_the_next_token() {
+            let input = "/// Adds two numbers.\n///\n///    Indented.\r\n//// Not a doc comment.\nfun add() {}";
+            let mut tokenizer = Tokenizer::new(input);
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+            assert!(errors.is_empty(), "{:?}", errors);
+
+            assert_eq!(tokens[0].kind, TokenKind::Fun);
+            assert_eq!(tokens[0].doc, Some("Adds two numbers.\n\n   Indented.".into()));
+            assert_eq!(tokens[1].doc, None);
+        }
+
+        #[test]
+        fn regular_comments_arent_docs() {
+            let mut tokenizer = Tokenizer::new("// One.\n/* Two. */\n//// Three.\nvar a;");
+
+            let (tokens, _) = tokenizer.scan_tokens();
+
+            assert!(tokens.iter().all(|token| token.doc.is_none()));
+        }
+    }
+
+    mod trivia {
+        use super::*;
+
+        fn reproduce(tokens: &Vec<Token>) -> String {
+            let mut ret = String::new();
+
+            for token in tokens {
+                for trivia in &token.leading_trivia {
+                    ret += trivia.text.as_str();
+                }
+                ret += token.lexeme.as_str();
+                for trivia in &token.trailing_trivia {
+                    ret += trivia.text.as_str();
+                }
+            }
+
+            return ret;
+        }
+
+        #[test]
+        fn tokens_reproduce_the_source() {
+            let inputs = [
+                "",
+                "   \n\t\n",
+                "var a = 1; // one\r\n\r\n/* two /* nested */ */ print a;",
+                "/// Doc.\nfun f(x) {\n\treturn \"${x} \\u{1F600}\";\n}\n",
+                "print \"\"\"multi\nline ${ \"nested\" }\"\"\";   ",
+                "var café = 0x_; ^ \"unterminated\nprint 1 → 2; /* unterminated",
+            ];
+
+            for input in inputs {
+                let mut tokenizer = Tokenizer::with_trivia(input);
+                let (tokens, _) = tokenizer.scan_tokens();
+
+                assert_eq!(reproduce(tokens), input);
+
+                for token in tokens {
+                    for trivia in token.leading_trivia.iter().chain(&token.trailing_trivia) {
+                        assert_eq!(&input[trivia.span.start..trivia.span.end], trivia.text);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn trailing_trivia_ends_with_the_line() {
+            let input = "a;  // one\n\n  // two\n  b /* three */";
+            let mut tokenizer = Tokenizer::with_trivia(input);
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+            assert!(errors.is_empty(), "{:?}", errors);
+
+            fn describe(trivia: &[Trivia]) -> Vec<(TriviaKind, &str)> {
+                return trivia.iter().map(|piece| (piece.kind, piece.text.as_str())).collect();
+            }
+
+            let semicolon = &tokens[1];
+            assert_eq!(
+                describe(&semicolon.trailing_trivia),
+                vec![
+                    (TriviaKind::Whitespace, "  "),
+                    (TriviaKind::LineComment, "// one"),
+                    (TriviaKind::Newline, "\n"),
+                ]
+            );
+
+            let b = &tokens[2];
+            assert_eq!(
+                describe(&b.leading_trivia),
+                vec![
+                    (TriviaKind::Newline, "\n"),
+                    (TriviaKind::Whitespace, "  "),
+                    (TriviaKind::LineComment, "// two"),
+                    (TriviaKind::Newline, "\n"),
+                    (TriviaKind::Whitespace, "  "),
+                ]
+            );
+            assert_eq!(
+                describe(&b.trailing_trivia),
+                vec![(TriviaKind::Whitespace, " "), (TriviaKind::BlockComment, "/* three */")]
+            );
+            assert!(tokens[3].leading_trivia.is_empty());
+        }
+
+        #[test]
+        fn text_left_out_by_errors_is_skipped_trivia() {
+            let mut tokenizer = Tokenizer::with_trivia("a ^ b");
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+
+            assert_eq!(errors.len(), 1);
+            assert_eq!(tokens[0].trailing_trivia[1].kind, TriviaKind::Skipped);
+            assert_eq!(tokens[0].trailing_trivia[1].text, "^");
+        }
+
+        #[test]
+        fn only_kept_when_asked_for() {
+            let mut tokenizer = Tokenizer::new("a // one\n b");
+
+            let (tokens, _) = tokenizer.scan_tokens();
+
+            assert!(tokens
+                .iter()
+                .all(|token| token.leading_trivia.is_empty() && token.trailing_trivia.is_empty()));
+        }
+    }
+
+    mod string_literals {
+        use super::*;
+
+        fn string_value(input: &str) -> String {
+            let mut tokenizer = Tokenizer::new(input);
+            let (tokens, errors) = tokenizer.scan_tokens();
+            assert!(errors.is_empty(), "{:?}", errors);
+
+            let Some(Literal::String(value)) = &tokens[0].literal else {
+                panic!("Expected a string literal.");
+            };
+
+            return value.clone();
+        }
+
+        #[test]
+        fn escape_sequences() {
+            assert_eq!(string_value(r#""a\nb\tc\r\0""#), "a\nb\tc\r\0");
+            assert_eq!(string_value(r#""say \"hi\" \\ bye""#), "say \"hi\" \\ bye");
+        }
+
+        #[test]
+        fn unicode_escapes() {
+            assert_eq!(string_value(r#""\u{1F600}""#), "\u{1F600}");
+            assert_eq!(string_value(r#""\u{e9}t\u{E9}""#), "été");
+        }
+
+        #[test]
+        fn bad_escapes_point_at_the_escape() {
+            let input = "var a = \"ok\";\nprint \"x\\qy \\u{D800} \\u{110000} \\u{} \\u12\";";
+            let mut tokenizer = Tokenizer::new(input);
+
+            let (_, errors) = tokenizer.scan_tokens();
+            let found: Vec<(&str, usize, &str)> = errors
+                .iter()
+                .map(|err| {
+                    let span = err.span.unwrap();
+                    (err.msg.as_str(), err.column, &input[span.start..span.end])
+                })
+                .collect();
+
+            assert_eq!(
+                found,
+                vec![
+                    ("Unknown escape sequence \"\\q\".", 9, "\\q"),
+                    ("\"\\u{D800}\" isn't a Unicode character.", 13, "\\u{D800}"),
+                    ("\"\\u{110000}\" isn't a Unicode character.", 22, "\\u{110000}"),
+                    ("A Unicode escape sequence must have 1 to 6 hexadecimal digits.", 33, "\\u{}"),
+                    ("Expected \"{\" after \"\\u\".", 38, "\\u"),
+                ]
+            );
+            assert!(errors.iter().all(|err| err.line == Some(2)));
+        }
+
+        #[test]
+        fn backslash_at_the_end_of_a_line() {
+            let mut tokenizer = Tokenizer::new("\"abc\\\nprint 1;");
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+            let messages: Vec<&str> = errors.iter().map(|err| err.msg.as_str()).collect();
+
+            assert_eq!(
+                messages,
+                vec!["Expected an escape sequence after \"\\\".", "Unterminated string."]
+            );
+            assert_eq!(tokens[0].kind, TokenKind::Print);
+            assert_eq!(tokens[0].line, 2);
+        }
+
+        #[test]
+        fn triple_quoted_strings_span_lines() {
+            let input = "print \"\"\"one\n  \"two\"\\t\nthree\"\"\";\nprint 1;";
+            let mut tokenizer = Tokenizer::new(input);
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+            assert!(errors.is_empty(), "{:?}", errors);
+
+            let string = &tokens[1];
+            assert_eq!(string.literal, Some(Literal::String("one\n  \"two\"\t\nthree".into())));
+            assert_eq!((string.line, string.column), (1, 7));
+            assert_eq!(string.span, Span::new(6, input.find(';').unwrap()));
+
+            // The lines inside the string are counted.
+            let semicolon = &tokens[2];
+            assert_eq!((semicolon.line, semicolon.column), (3, 9));
+            let print = &tokens[3];
+            assert_eq!((print.line, print.column), (4, 1));
+        }
+
+        #[test]
+        fn empty_strings() {
+            assert_eq!(string_value("\"\""), "");
+            assert_eq!(string_value("\"\"\"\"\"\""), "");
+        }
+
+        #[test]
+        fn unterminated_triple_quoted_string() {
+            let mut tokenizer = Tokenizer::new("var a;\nvar b = \"\"\"one\ntwo\"\";");
+
+            let (_, errors) = tokenizer.scan_tokens();
+
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].msg, "Unterminated string.");
+            assert_eq!((errors[0].line, errors[0].column), (Some(2), 9));
+        }
+    }
+
+    mod interpolation {
+        use super::*;
+
+        fn kinds(input: &str) -> Vec<TokenKind> {
+            let mut tokenizer = Tokenizer::new(input);
+            let (tokens, errors) = tokenizer.scan_tokens();
+            assert!(errors.is_empty(), "{:?}", errors);
+
+            return tokens.iter().map(|token| token.kind.clone()).collect();
+        }
+
+        #[test]
+        fn splits_the_string_around_expressions() {
+            let input = "\"Hello, ${name}! You have ${count + 1} items\"";
+            let mut tokenizer = Tokenizer::new(input);
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+            assert!(errors.is_empty(), "{:?}", errors);
+
+            let found: Vec<(TokenKind, &str)> = tokens
+                .iter()
+                .map(|token| (token.kind.clone(), token.lexeme.as_str()))
+                .collect();
+            assert_eq!(
+                found,
+                vec![
+                    (TokenKind::Interpolation, "\"Hello, ${"),
+                    (TokenKind::Identifier, "name"),
+                    (TokenKind::Interpolation, "}! You have ${"),
+                    (TokenKind::Identifier, "count"),
+                    (TokenKind::Plus, "+"),
+                    (TokenKind::Number, "1"),
+                    (TokenKind::String, "} items\""),
+                    (TokenKind::Eof, ""),
+                ]
+            );
+            assert_eq!(tokens[0].literal, Some(Literal::String("Hello, ".into())));
+            assert_eq!(tokens[2].literal, Some(Literal::String("! You have ".into())));
+            assert_eq!(tokens[6].literal, Some(Literal::String(" items".into())));
+        }
+
+        #[test]
+        fn nested_strings_and_braces() {
+            assert_eq!(
+                kinds("\"a ${ \"b ${c}\" + \"}\" } d\";"),
+                vec![
+                    TokenKind::Interpolation,
+                    TokenKind::Interpolation,
+                    TokenKind::Identifier,
+                    TokenKind::String,
+                    TokenKind::Plus,
+                    TokenKind::String,
+                    TokenKind::String,
+                    TokenKind::Semicolon,
+                    TokenKind::Eof,
+                ]
+            );
+            assert_eq!(
+                kinds("\"${ f({ }) }\""),
+                vec![
+                    TokenKind::Interpolation,
+                    TokenKind::Identifier,
+                    TokenKind::LeftParen,
+                    TokenKind::LeftBrace,
+                    TokenKind::RightBrace,
+                    TokenKind::RightParen,
+                    TokenKind::String,
+                    TokenKind::Eof,
+                ]
+            );
+        }
+
+        #[test]
+        fn escaped_dollar_sign() {
+            let mut tokenizer = Tokenizer::new("\"\\${a} $a\"");
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+
+            assert!(errors.is_empty(), "{:?}", errors);
+            assert_eq!(tokens[0].literal, Some(Literal::String("${a} $a".into())));
+        }
+
+        #[test]
+        fn in_triple_quoted_strings() {
+            let input = "\"\"\"one ${a}\ntwo\"\"\" b";
+            let mut tokenizer = Tokenizer::new(input);
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+            assert!(errors.is_empty(), "{:?}", errors);
+
+            assert_eq!(tokens[2].literal, Some(Literal::String("\ntwo".into())));
+            assert_eq!((tokens[3].lexeme.as_str(), tokens[3].line), ("b", 2));
+        }
+
+        #[test]
+        fn unterminated_interpolation() {
+            let mut tokenizer = Tokenizer::new("print \"a ${b + 1;");
+
+            let (_, errors) = tokenizer.scan_tokens();
+
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].msg, "Unterminated interpolation.");
+            assert_eq!(errors[0].column, 10);
+            assert_eq!(errors[0].span, Some(Span::new(9, 11)));
+        }
+    }
+
+    mod unicode {
+        use super::*;
+
+        #[test]
+        fn multi_byte_strings_and_comments() {
+            let input = "var cafe = \"naïve 😀\"; // Ünïcödé.\nprint cafe;";
+            let mut tokenizer = Tokenizer::new(input);
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+            assert!(errors.is_empty(), "{:?}", errors);
+
+            assert_eq!(tokens[3].literal, Some(Literal::String("naïve 😀".into())));
+            assert_eq!((tokens[6].lexeme.as_str(), tokens[6].line, tokens[6].column), ("cafe", 2, 7));
+            assert_eq!(tokens.len(), 9);
+        }
+
+        #[test]
+        fn columns_count_characters_and_spans_count_bytes() {
+            let input = "\"ü😀\" + e;";
+            let mut tokenizer = Tokenizer::new(input);
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+            assert!(errors.is_empty(), "{:?}", errors);
+
+            let plus = &tokens[1];
+            assert_eq!(plus.column, 6);
+            assert_eq!(plus.span, Span::new(9, 10));
+
+            let identifier = &tokens[2];
+            assert_eq!(identifier.column, 8);
+            assert_eq!(&input[identifier.span.start..identifier.span.end], "e");
+
+            let eof = &tokens[4];
+            assert_eq!(eof.column, 10);
+            assert_eq!(eof.span, Span::new(input.len(), input.len()));
+        }
+
+        #[test]
+        fn errors_after_multi_byte_characters() {
+            let input = "var a = \"ö\" ^ \"\\ü\";";
+            let mut tokenizer = Tokenizer::new(input);
+
+            let (_, errors) = tokenizer.scan_tokens();
+            let found: Vec<(&str, usize, &str)> = errors
+                .iter()
+                .map(|err| {
+                    let span = err.span.unwrap();
+                    (err.msg.as_str(), err.column, &input[span.start..span.end])
+                })
+                .collect();
+
+            assert_eq!(
+                found,
+                vec![
+                    ("Unrecognized character \"^\".", 13, "^"),
+                    ("Unknown escape sequence \"\\ü\".", 16, "\\ü"),
+                ]
+            );
+        }
+
+        #[test]
+        fn unrecognized_multi_byte_character() {
+            let input = "a → b";
+            let mut tokenizer = Tokenizer::new(input);
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+
+            assert_eq!(tokens.len(), 3);
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].msg, "Unrecognized character \"→\".");
+            assert_eq!(errors[0].span, Some(Span::new(2, 5)));
+            assert_eq!(tokens[1].column, 5);
+        }
+    }
+
+    mod numbers {
+        use super::*;
+
+        fn number(input: &str) -> f64 {
+            let mut tokenizer = Tokenizer::new(input);
+            let (tokens, errors) = tokenizer.scan_tokens();
+            assert!(errors.is_empty(), "{:?}", errors);
+            assert_eq!(tokens.len(), 2, "{:?}", tokens);
+
+            let Some(Literal::Number(value)) = tokens[0].literal else {
+                panic!("Expected a number literal.");
+            };
+
+            return value;
+        }
+
+        fn error(input: &str) -> String {
+            let mut tokenizer = Tokenizer::new(input);
+            let (_, errors) = tokenizer.scan_tokens();
+            assert_eq!(errors.len(), 1, "{:?}", errors);
+
+            return errors[0].msg.clone();
+        }
+
+        #[test]
+        fn decimals() {
+            assert_eq!(number("42"), 42.0);
+            assert_eq!(number("3.25"), 3.25);
+            assert_eq!(number("1e-9"), 1e-9);
+            assert_eq!(number("6.02E23"), 6.02e23);
+            assert_eq!(number("2e+3"), 2000.0);
+            assert_eq!(number("1_000_000"), 1_000_000.0);
+            assert_eq!(number("0.000_1"), 0.0001);
+        }
+
+        #[test]
+        fn other_radixes() {
+            assert_eq!(number("0xFF"), 255.0);
+            assert_eq!(number("0Xdead_beef"), 3735928559.0);
+            assert_eq!(number("0b1010"), 10.0);
+            assert_eq!(number("0o755"), 493.0);
+            assert_eq!(number("0x1e5"), 485.0);
+        }
+
+        #[test]
+        fn a_dot_without_digits_isnt_a_fraction() {
+            let mut tokenizer = Tokenizer::new("1.foo");
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+            let kinds: Vec<TokenKind> = tokens.iter().map(|token| token.kind.clone()).collect();
+
+            assert!(errors.is_empty(), "{:?}", errors);
+            assert_eq!(
+                kinds,
+                vec![TokenKind::Number, TokenKind::Dot, TokenKind::Identifier, TokenKind::Eof]
+            );
+        }
+
+        #[test]
+        fn malformed_numbers() {
+            assert_eq!(error("0x"), "Expected digits after \"0x\".");
+            assert_eq!(error("0B"), "Expected digits after \"0B\".");
+            assert_eq!(error("0b102"), "Invalid digit \"2\" in binary number.");
+            assert_eq!(error("0o78"), "Invalid digit \"8\" in octal number.");
+            assert_eq!(error("0xFG"), "Invalid digit \"G\" in hexadecimal number.");
+            assert_eq!(error("12abc"), "Invalid digit \"a\" in decimal number.");
+            assert_eq!(error("1e"), "Expected digits in the exponent of \"1e\".");
+            assert_eq!(error("1.5e-"), "Expected digits in the exponent of \"1.5e\".");
+            assert_eq!(error("1__000"), "Underscores in numbers can only separate digits.");
+            assert_eq!(error("1_"), "Underscores in numbers can only separate digits.");
+            assert_eq!(error("0x_1"), "Underscores in numbers can only separate digits.");
+            assert_eq!(error("1_.5"), "Underscores in numbers can only separate digits.");
+        }
+
+        #[test]
+        fn errors_cover_the_whole_literal() {
+            let input = "var a = 0b1021;";
+            let mut tokenizer = Tokenizer::new(input);
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+            let span = errors[0].span.unwrap();
+
+            assert_eq!(&input[span.start..span.end], "0b1021");
+            assert_eq!(errors[0].column, 9);
+            assert_eq!(tokens[4].kind, TokenKind::Semicolon);
+        }
+
+        #[test]
+        fn non_ascii_digits_arent_numbers() {
+            let mut tokenizer = Tokenizer::new("٣");
+
+            let (_, errors) = tokenizer.scan_tokens();
+
+            assert_eq!(errors[0].msg, "Unrecognized character \"٣\".");
+        }
+    }
+
+    mod keywords {
+        use super::*;
+
+        fn lexemes(input: &str) -> Vec<String> {
+            let mut tokenizer = Tokenizer::new(input);
+            let (tokens, errors) = tokenizer.scan_tokens();
+            assert!(errors.is_empty(), "{:?}", errors);
+
+            return tokens.iter().map(|token| token.lexeme.clone()).collect();
+        }
+
+        #[test]
+        fn identifiers_end_at_any_other_character() {
+            assert_eq!(lexemes("a+b"), vec!["a", "+", "b", ""]);
+            assert_eq!(lexemes("x=1;"), vec!["x", "=", "1", ";", ""]);
+            assert_eq!(lexemes("a<b!=c"), vec!["a", "<", "b", "!=", "c", ""]);
+            assert_eq!(lexemes("\"${name}\""), vec!["\"${", "name", "}\"", ""]);
+        }
+
+        #[test]
+        fn identifiers_have_letters_digits_and_underscores() {
+            let mut tokenizer = Tokenizer::new("_private snake_case2 x1y _ ifelse");
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+
+            assert!(errors.is_empty(), "{:?}", errors);
+            assert_eq!(tokens.len(), 6);
+            assert!(tokens[..5].iter().all(|token| token.kind == TokenKind::Identifier));
+        }
+
+        #[test]
+        fn non_ascii_letters_arent_identifiers() {
+            let mut tokenizer = Tokenizer::new("café");
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+
+            assert_eq!(tokens[0].lexeme, "caf");
+            assert_eq!(errors[0].msg, "Unrecognized character \"é\".");
+        }
+
+        #[test]
+        fn else_and_if_are_separate_tokens() {
+            for input in ["else if", "else  if", "else\nif", "else /* comment */ if"] {
+                let mut tokenizer = Tokenizer::new(input);
+
+                let (tokens, _) = tokenizer.scan_tokens();
+                let kinds: Vec<TokenKind> = tokens.iter().map(|token| token.kind.clone()).collect();
+
+                assert_eq!(kinds, vec![TokenKind::Else, TokenKind::If, TokenKind::Eof]);
+            }
+        }
+
+        #[test]
+        fn loop_labels() {
+            let input = "outer: while true { break outer; continue; }";
+            let mut tokenizer = Tokenizer::new(input);
+
+            let (tokens, errors) = tokenizer.scan_tokens();
+            let kinds: Vec<TokenKind> = tokens.iter().map(|token| token.kind.clone()).collect();
+
+            assert_eq!(errors.len(), 0);
+            assert_eq!(
+                kinds,
+                vec![
+                    TokenKind::Identifier,
+                    TokenKind::Colon,
+                    TokenKind::While,
+                    TokenKind::True,
+                    TokenKind::LeftBrace,
+                    TokenKind::Break,
+                    TokenKind::Identifier,
+                    TokenKind::Semicolon,
+                    TokenKind::Continue,
+                    TokenKind::Semicolon,
+                    TokenKind::RightBrace,
+                    TokenKind::Eof,
+                ]
+            );
+        }
+    }
+
     mod handling_errors {
         use crate::tokenizer::Tokenizer;
 
@@ -0,0 +1,37 @@
+use std::fmt::Display;
+
+use crate::span::Span;
+
+/// TriviaKind is the kind of a piece of source code between tokens, which doesn't change what the
+/// program does.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TriviaKind {
+    /// Spaces and tabs.
+    Whitespace,
+    /// A line break, either `\n` or `\r\n`.
+    Newline,
+    /// A `//` comment, without the line break that ends it.
+    LineComment,
+    /// A `///` doc comment, without the line break that ends it.
+    DocComment,
+    /// A `/* ... */` comment, with the comments nested in it.
+    BlockComment,
+    /// Text that isn't part of any token because of an error, like an unrecognized character or
+    /// an unterminated string.
+    Skipped,
+}
+
+/// Trivia is a piece of whitespace or a comment kept on a token, so tools can reproduce the source
+/// code from the tokens.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub text: String,
+    pub span: Span,
+}
+
+impl Display for Trivia {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{:?} {:?}", self.kind, self.text);
+    }
+}
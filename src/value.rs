@@ -0,0 +1,117 @@
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::rc::Rc;
+
+use crate::class::{Class, Instance};
+use crate::function::{Function, NativeFunction};
+use crate::literal::Literal;
+
+/// Value is anything a Lox expression can evaluate to at runtime.
+///
+/// Numbers, strings, booleans and nil are plain data, copied on assignment. Everything else lives
+/// on the heap behind an `Rc`, so copies of it share the same object.
+#[derive(Debug, Clone)]
+pub enum Value {
+    Number(f64),
+    String(String),
+    Boolean(bool),
+    Nil,
+    /// A user-defined function.
+    Function(Rc<Function>),
+    /// A function built into the interpreter, such as `clock`.
+    NativeFunction(Rc<NativeFunction>),
+    /// A user-defined class.
+    Class(Rc<Class>),
+    /// An instance of a user-defined class.
+    Instance(Rc<RefCell<Instance>>),
+    /// A list of values. Lists are mutable, so every copy sees the changes made through another.
+    List(Rc<RefCell<Vec<Value>>>),
+}
+
+impl Value {
+    pub fn to_string(&self) -> String {
+        match self {
+            Value::Number(n) => n.to_string(),
+            Value::String(s) => s.to_string(),
+            Value::Boolean(b) => b.to_string(),
+            Value::Nil => "nil".into(),
+            Value::Function(function) => format!("{:?}", function),
+            Value::NativeFunction(function) => format!("{:?}", function),
+            Value::Class(class) => format!("{:?}", class),
+            Value::Instance(instance) => format!("{:?}", instance.borrow()),
+            Value::List(list) => {
+                let elements: Vec<String> = list.borrow().iter().map(|element| element.to_string()).collect();
+
+                format!("[{}]", elements.join(", "))
+            }
+        }
+    }
+}
+
+impl From<Literal> for Value {
+    fn from(literal: Literal) -> Self {
+        return match literal {
+            Literal::Number(n) => Value::Number(n),
+            Literal::String(s) => Value::String(s),
+            Literal::Boolean(b) => Value::Boolean(b),
+            Literal::Nil => Value::Nil,
+        };
+    }
+}
+
+impl PartialEq for Value {
+    /// Plain data is equal when it holds the same data. Heap objects are only equal to themselves.
+    fn eq(&self, other: &Self) -> bool {
+        return match (self, other) {
+            (Value::Number(left), Value::Number(right)) => left == right,
+            (Value::String(left), Value::String(right)) => left == right,
+            (Value::Boolean(left), Value::Boolean(right)) => left == right,
+            (Value::Nil, Value::Nil) => true,
+            (Value::Function(left), Value::Function(right)) => Rc::ptr_eq(left, right),
+            (Value::NativeFunction(left), Value::NativeFunction(right)) => Rc::ptr_eq(left, right),
+            (Value::Class(left), Value::Class(right)) => Rc::ptr_eq(left, right),
+            (Value::Instance(left), Value::Instance(right)) => Rc::ptr_eq(left, right),
+            (Value::List(left), Value::List(right)) => Rc::ptr_eq(left, right),
+            _ => false,
+        };
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{}", self.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literals_become_plain_values() {
+        assert_eq!(Value::from(Literal::Number(1.5)), Value::Number(1.5));
+        assert_eq!(Value::from(Literal::String("a".into())), Value::String("a".into()));
+        assert_eq!(Value::from(Literal::Boolean(true)), Value::Boolean(true));
+        assert_eq!(Value::from(Literal::Nil), Value::Nil);
+    }
+
+    #[test]
+    fn lists_are_equal_only_to_themselves() {
+        let list = Value::List(Rc::new(RefCell::new(vec![Value::Number(1.0)])));
+        let same_elements = Value::List(Rc::new(RefCell::new(vec![Value::Number(1.0)])));
+
+        assert_eq!(list, list.clone());
+        assert_ne!(list, same_elements);
+    }
+
+    #[test]
+    fn lists_print_their_elements() {
+        let list = Value::List(Rc::new(RefCell::new(vec![
+            Value::Number(1.0),
+            Value::String("two".into()),
+            Value::Nil,
+        ])));
+
+        assert_eq!(list.to_string(), "[1, two, nil]");
+    }
+}